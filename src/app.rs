@@ -0,0 +1,207 @@
+use crate::audit::PredictionAuditor;
+use crate::auth::{self, Authenticator};
+use crate::config::Config;
+use crate::handlers::{admin, health, models, predict, slo};
+use crate::limits::{self, RequestLimits};
+use crate::logging::{self, LogLevelHandle};
+use crate::metrics::{
+    self,
+    drift::DriftMonitor,
+    output::OutputMonitor,
+    prometheus::{self, AppMetrics},
+    slo::SloTracker,
+};
+use crate::openapi::ApiDoc;
+use crate::rate_limit::{self, RateLimiter};
+use crate::readiness::Readiness;
+use crate::{request_id, telemetry};
+use anyhow::Result;
+use axum::{
+    extract::DefaultBodyLimit,
+    http::HeaderValue,
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use std::sync::Arc;
+use tower_http::{
+    cors::{AllowOrigin, CorsLayer},
+    trace::TraceLayer,
+};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+/// Shared state available to handlers
+#[derive(Clone)]
+pub struct AppState {
+    pub config: Arc<Config>,
+    pub readiness: Arc<Readiness>,
+    pub limits: Arc<RequestLimits>,
+    /// Metrics of this instance, served at `/metrics`
+    pub metrics: Arc<AppMetrics>,
+    /// Present when `drift.enabled` is set
+    pub drift: Option<Arc<DriftMonitor>>,
+    /// Present when `output_drift.enabled` is set
+    pub outputs: Option<Arc<OutputMonitor>>,
+    /// Present when `slo.enabled` is set
+    pub slo: Option<Arc<SloTracker>>,
+    /// Present when `auth.enabled` is set
+    pub authenticator: Option<Arc<Authenticator>>,
+    /// Present when `rate_limit.enabled` is set
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Set by `main` once the log subscriber is installed; `/admin/log-level`
+    /// answers 503 without it
+    pub log_level: Option<LogLevelHandle>,
+    /// Set by `main` when `audit.enabled` is set, since it owns the writer
+    pub auditor: Option<Arc<PredictionAuditor>>,
+}
+
+impl AppState {
+    pub fn new(config: Config) -> Result<Self> {
+        let mut metrics = AppMetrics::from_config(&config.metrics)?;
+        let slo = if config.slo.enabled {
+            let slo = Arc::new(SloTracker::new(config.slo.clone())?);
+            metrics.track_slo(slo.clone())?;
+            Some(slo)
+        } else {
+            None
+        };
+        let metrics = Arc::new(metrics);
+        let drift = if config.drift.enabled {
+            let drift = Arc::new(DriftMonitor::new(config.drift.clone())?);
+            drift.register(metrics.registry())?;
+            Some(drift)
+        } else {
+            None
+        };
+        let outputs = if config.output_drift.enabled {
+            let outputs = Arc::new(OutputMonitor::new(config.output_drift.clone())?);
+            outputs.register(metrics.registry())?;
+            Some(outputs)
+        } else {
+            None
+        };
+        let readiness = Readiness::from_config(&config.readiness);
+        let limits = RequestLimits::from_config(&config, metrics.clone());
+        let authenticator = if config.auth.enabled {
+            Some(Arc::new(Authenticator::from_config(&config.auth)?))
+        } else {
+            None
+        };
+
+        let rate_limiter = config
+            .rate_limit
+            .enabled
+            .then(|| Arc::new(RateLimiter::new(config.rate_limit.clone(), metrics.clone())));
+
+        Ok(Self {
+            config: Arc::new(config),
+            readiness: Arc::new(readiness),
+            limits: Arc::new(limits),
+            metrics,
+            drift,
+            outputs,
+            slo,
+            authenticator,
+            rate_limiter,
+            log_level: None,
+            auditor: None,
+        })
+    }
+}
+
+/// Build the application router with all API routes
+///
+/// Also serves the generated OpenAPI document at `/openapi.json` and the
+/// Swagger UI at `/docs`. Every route added here must be listed in `ApiDoc`.
+pub fn create_router(state: AppState) -> Router {
+    let config = state.config.clone();
+    let authenticator = state.authenticator.clone();
+
+    // Rate limiting runs after authentication so it can key by credential
+    let mut predict_route = post(predict::predict);
+    if let Some(rate_limiter) = state.rate_limiter.clone() {
+        predict_route = predict_route.route_layer(middleware::from_fn_with_state(
+            rate_limiter,
+            rate_limit::rate_limit_middleware,
+        ));
+    }
+
+    let router = Router::new()
+        .route("/health", get(health::health_check))
+        .route("/livez", get(health::liveness))
+        .route("/readyz", get(health::readiness))
+        .route("/predict", predict_route)
+        .route("/models", get(models::list_models))
+        .route("/models/{name}", get(models::get_model_metadata))
+        .route("/models/{name}/drift", get(models::get_model_drift))
+        .route("/metrics", get(prometheus::metrics_handler))
+        .route("/slo", get(slo::get_slo))
+        .route(
+            "/admin/log-level",
+            get(admin::get_log_level).put(admin::set_log_level),
+        )
+        .merge(SwaggerUi::new("/docs").url("/openapi.json", ApiDoc::openapi()));
+
+    // Limits run inside authentication, so requests without valid credentials
+    // are turned away before they take a concurrency slot or count towards
+    // load shedding. Bodies declaring an oversized `Content-Length` are
+    // rejected before admission too; only streamed bodies that grow past the
+    // limit are caught by `DefaultBodyLimit` while holding a slot.
+    let router = router
+        .layer(DefaultBodyLimit::max(config.limits.max_body_bytes))
+        .layer(middleware::from_fn_with_state(
+            state.limits.clone(),
+            limits::limits_middleware,
+        ));
+
+    // Authentication sits inside CORS so preflight requests never need credentials
+    let router = match authenticator {
+        Some(authenticator) => router.layer(middleware::from_fn_with_state(
+            authenticator,
+            auth::auth_middleware,
+        )),
+        None => router,
+    };
+
+    router
+        .layer(cors_layer(&config))
+        // Outside every layer that can reject, so rejections are counted too,
+        // and inside the request span so exemplars can carry its trace ID
+        .layer(middleware::from_fn_with_state(
+            state.metrics.clone(),
+            metrics::middleware::metrics_middleware,
+        ))
+        // The request ID is assigned outside the trace layer so its span can carry it
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(telemetry::make_request_span)
+                .on_response(logging::log_response),
+        )
+        .layer(middleware::from_fn(request_id::request_id_middleware))
+        .with_state(state)
+}
+
+/// Whether `path` falls under `prefix`, matching whole path segments only
+/// (`/health` matches `/health` and `/health/x` but not `/healthz`)
+pub fn path_matches_prefix(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'))
+}
+
+/// CORS policy from the configured origins; `"*"` allows any origin
+fn cors_layer(config: &Config) -> CorsLayer {
+    if config.cors.allowed_origins.iter().any(|o| o == "*") {
+        return CorsLayer::permissive();
+    }
+
+    // Origins are validated as header values when the config is loaded
+    let origins = config
+        .cors
+        .allowed_origins
+        .iter()
+        .filter_map(|origin| HeaderValue::from_str(origin).ok())
+        .collect::<Vec<_>>();
+
+    CorsLayer::permissive().allow_origin(AllowOrigin::list(origins))
+}
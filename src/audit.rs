@@ -0,0 +1,242 @@
+use crate::config::AuditConfig;
+use crate::logging::RotatingFile;
+use crate::metrics::prometheus::AppMetrics;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+/// One line of the prediction audit log
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditRecord {
+    pub request_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub model_version: String,
+    /// Authenticated caller, when authentication is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    pub features: Vec<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prediction: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// Out-of-distribution score, when the model has a reference profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ood_score: Option<f64>,
+    /// Why the prediction failed, for rejected inputs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub latency_ms: f64,
+}
+
+enum Message {
+    Record(AuditRecord),
+    Shutdown,
+}
+
+/// Hands prediction records to the background writer without blocking
+///
+/// When the buffer is full the record is dropped and counted, so a slow disk
+/// never adds latency to predictions.
+pub struct PredictionAuditor {
+    tx: mpsc::Sender<Message>,
+    sample_rate: f64,
+    dropped: AtomicU64,
+    metrics: Arc<AppMetrics>,
+}
+
+/// Background thread appending records to the audit file
+pub struct AuditWriter {
+    tx: mpsc::Sender<Message>,
+    thread: JoinHandle<()>,
+}
+
+/// Open the audit file and start the writer thread
+pub fn spawn(
+    config: &AuditConfig,
+    metrics: Arc<AppMetrics>,
+) -> Result<(Arc<PredictionAuditor>, AuditWriter)> {
+    let file = RotatingFile::open(&config.path, config.max_file_bytes, config.max_files)
+        .context("Failed to open prediction audit log")?;
+    let (auditor, rx) = PredictionAuditor::channel(config, metrics.clone());
+    let tx = auditor.tx.clone();
+
+    let thread = std::thread::Builder::new()
+        .name("prediction-audit".to_string())
+        .spawn(move || write_records(file, rx, &metrics))
+        .context("Failed to start prediction audit writer")?;
+
+    Ok((Arc::new(auditor), AuditWriter { tx, thread }))
+}
+
+impl PredictionAuditor {
+    fn channel(config: &AuditConfig, metrics: Arc<AppMetrics>) -> (Self, mpsc::Receiver<Message>) {
+        let (tx, rx) = mpsc::channel(config.buffer_size);
+        let auditor = Self {
+            tx,
+            sample_rate: config.sample_rate,
+            dropped: AtomicU64::new(0),
+            metrics,
+        };
+        (auditor, rx)
+    }
+
+    /// Queue a record if its request is sampled
+    pub fn record(&self, record: AuditRecord) {
+        if !is_sampled(&record.request_id, self.sample_rate) {
+            return;
+        }
+
+        if let Err(e) = self.tx.try_send(Message::Record(record)) {
+            let reason = match e {
+                TrySendError::Full(_) => "buffer_full",
+                TrySendError::Closed(_) => "closed",
+            };
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            self.metrics.record_audit_dropped(reason);
+        }
+    }
+
+    /// Records dropped because the buffer was full or the writer had stopped
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl AuditWriter {
+    /// Write every record queued so far, sync the file and stop the thread
+    pub async fn shutdown(self) -> Result<()> {
+        // Waits for buffer space, unlike `record`, so nothing queued is lost
+        self.tx
+            .send(Message::Shutdown)
+            .await
+            .context("Prediction audit writer stopped unexpectedly")?;
+
+        tokio::task::spawn_blocking(move || self.thread.join())
+            .await?
+            .map_err(|_| anyhow::anyhow!("Prediction audit writer panicked"))
+    }
+}
+
+/// Deterministic per-request sampling, so retries of a request ID are
+/// either all recorded or all skipped
+fn is_sampled(request_id: &str, sample_rate: f64) -> bool {
+    if sample_rate >= 1.0 {
+        return true;
+    }
+
+    let digest = Sha256::digest(request_id.as_bytes());
+    let bucket = u64::from_be_bytes(digest[..8].try_into().expect("digest has 8 bytes"));
+    (bucket as f64 / u64::MAX as f64) < sample_rate
+}
+
+fn write_records(file: RotatingFile, mut rx: mpsc::Receiver<Message>, metrics: &AppMetrics) {
+    while let Some(Message::Record(record)) = rx.blocking_recv() {
+        let mut line = match serde_json::to_vec(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to serialize prediction audit record");
+                metrics.record_audit_dropped("serialize_error");
+                continue;
+            }
+        };
+        line.push(b'\n');
+
+        // One write per record keeps each line within a single file
+        if let Err(e) = (&file).write_all(&line) {
+            tracing::error!(error = %e, request_id = %record.request_id, "Failed to write prediction audit record");
+            metrics.record_audit_dropped("write_error");
+        }
+    }
+
+    if let Err(e) = file.sync() {
+        tracing::error!(error = %e, "Failed to sync prediction audit log");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(request_id: &str) -> AuditRecord {
+        AuditRecord {
+            request_id: request_id.to_string(),
+            timestamp: Utc::now(),
+            model_version: "v1.0.0".to_string(),
+            subject: None,
+            features: vec![1.0, 2.0],
+            prediction: Some(3.5),
+            confidence: Some(0.9),
+            ood_score: None,
+            error: None,
+            latency_ms: 0.2,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_written_as_ndjson() {
+        let dir = std::env::temp_dir().join(format!("audit-{}", uuid::Uuid::new_v4()));
+        let config = AuditConfig {
+            enabled: true,
+            path: dir.join("predictions.ndjson"),
+            ..AuditConfig::default()
+        };
+
+        let (auditor, writer) = spawn(&config, Arc::new(AppMetrics::new().unwrap())).unwrap();
+        auditor.record(record("req-1"));
+        auditor.record(record("req-2"));
+        writer.shutdown().await.unwrap();
+
+        let contents = std::fs::read_to_string(&config.path).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["request_id"], "req-1");
+        assert_eq!(lines[1]["features"], serde_json::json!([1.0, 2.0]));
+        assert_eq!(lines[1]["prediction"], 3.5);
+        assert!(lines[1].get("error").is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_full_buffer_drops_records() {
+        let config = AuditConfig {
+            buffer_size: 1,
+            ..AuditConfig::default()
+        };
+        // No writer consumes the channel, so the second record finds it full
+        let metrics = Arc::new(AppMetrics::new().unwrap());
+        let (auditor, _rx) = PredictionAuditor::channel(&config, metrics.clone());
+
+        auditor.record(record("req-1"));
+        auditor.record(record("req-2"));
+        assert_eq!(auditor.dropped(), 1);
+        assert_eq!(
+            metrics
+                .prediction_audit_dropped_total
+                .with_label_values(&["buffer_full"])
+                .get(),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_sampling() {
+        let sampled = (0..1000)
+            .filter(|i| is_sampled(&format!("req-{}", i), 0.25))
+            .count();
+        assert!((150..350).contains(&sampled), "sampled {}", sampled);
+
+        assert!(is_sampled("req-1", 1.0));
+        assert!(!is_sampled("req-1", 0.0));
+        assert_eq!(is_sampled("req-7", 0.5), is_sampled("req-7", 0.5));
+    }
+}
@@ -0,0 +1,541 @@
+use crate::app::path_matches_prefix;
+use crate::config::{AuthConfig, AuthPolicy};
+use anyhow::{anyhow, Context, Result};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+const API_KEY_HEADER: &str = "x-api-key";
+const ADMIN_ROLE: &str = "admin";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMethod {
+    ApiKey,
+    Jwt,
+}
+
+/// Authenticated caller, inserted as a request extension for handlers
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub subject: String,
+    pub method: AuthMethod,
+    pub roles: Vec<String>,
+    /// Rate limit tier, if the credential names one
+    pub tier: Option<String>,
+}
+
+impl Principal {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum AuthError {
+    /// No credentials, or credentials that failed validation
+    Unauthenticated(&'static str),
+    /// Valid credentials without the required role
+    Forbidden,
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        match self {
+            AuthError::Unauthenticated(reason) => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer, ApiKey")],
+                reason,
+            )
+                .into_response(),
+            AuthError::Forbidden => (StatusCode::FORBIDDEN, "insufficient role").into_response(),
+        }
+    }
+}
+
+struct ApiKey {
+    name: String,
+    hash: [u8; 32],
+    roles: Vec<String>,
+    tier: Option<String>,
+}
+
+struct JwtKey {
+    kid: Option<String>,
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
+struct JwtVerifier {
+    keys: Vec<JwtKey>,
+    validation: Validation,
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+    #[serde(default)]
+    roles: Vec<String>,
+    /// OAuth2-style space-separated scopes, treated as roles
+    #[serde(default)]
+    scope: Option<String>,
+    #[serde(default)]
+    tier: Option<String>,
+}
+
+/// Validates API keys and JWTs and applies per-route policies
+pub struct Authenticator {
+    api_keys: Vec<ApiKey>,
+    jwt: Option<JwtVerifier>,
+    default_policy: AuthPolicy,
+    routes: Vec<(String, AuthPolicy)>,
+}
+
+impl Authenticator {
+    /// Build from configuration, loading the JWKS file if JWTs are enabled
+    pub fn from_config(config: &AuthConfig) -> Result<Self> {
+        let api_keys = config
+            .api_keys
+            .iter()
+            .map(|api_key| {
+                let mut hash = [0u8; 32];
+                hex::decode_to_slice(&api_key.key_sha256, &mut hash).with_context(|| {
+                    format!(
+                        "Invalid key_sha256 for {}: expected 64 hex characters",
+                        api_key.name
+                    )
+                })?;
+                Ok(ApiKey {
+                    name: api_key.name.clone(),
+                    hash,
+                    roles: api_key.roles.clone(),
+                    tier: api_key.tier.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let jwt = match &config.jwt {
+            Some(jwt_config) => {
+                let jwks = std::fs::read_to_string(&jwt_config.jwks_path).with_context(|| {
+                    format!("Failed to read JWKS {}", jwt_config.jwks_path.display())
+                })?;
+                let jwks: JwkSet = serde_json::from_str(&jwks)
+                    .with_context(|| format!("Invalid JWKS {}", jwt_config.jwks_path.display()))?;
+
+                let keys = load_jwt_keys(&jwks)?;
+                let mut validation = Validation::new(Algorithm::RS256);
+                validation.algorithms = keys.iter().map(|key| key.algorithm).collect();
+                validation.leeway = jwt_config.leeway_secs;
+                match &jwt_config.audience {
+                    Some(audience) => validation.set_audience(&[audience]),
+                    None => validation.validate_aud = false,
+                }
+                if let Some(issuer) = &jwt_config.issuer {
+                    validation.set_issuer(&[issuer]);
+                }
+
+                Some(JwtVerifier { keys, validation })
+            }
+            None => None,
+        };
+
+        let mut routes: Vec<_> = config
+            .routes
+            .iter()
+            .map(|route| (route.prefix.clone(), route.policy))
+            .collect();
+        // Longest prefix first so the most specific policy wins
+        routes.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        Ok(Self {
+            api_keys,
+            jwt,
+            default_policy: config.default_policy,
+            routes,
+        })
+    }
+
+    /// Policy for a request path
+    pub fn policy_for(&self, path: &str) -> AuthPolicy {
+        self.routes
+            .iter()
+            .find(|(prefix, _)| path_matches_prefix(path, prefix))
+            .map(|(_, policy)| *policy)
+            .unwrap_or(self.default_policy)
+    }
+
+    fn authenticate(&self, headers: &HeaderMap) -> Result<Principal, AuthError> {
+        if let Some(api_key) = headers.get(API_KEY_HEADER) {
+            let api_key = api_key
+                .to_str()
+                .map_err(|_| AuthError::Unauthenticated("malformed API key"))?;
+            return self.authenticate_api_key(api_key);
+        }
+
+        if let Some(authorization) = headers.get(header::AUTHORIZATION) {
+            let token = authorization
+                .to_str()
+                .ok()
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .ok_or(AuthError::Unauthenticated("expected a Bearer token"))?;
+            return self.authenticate_jwt(token.trim());
+        }
+
+        Err(AuthError::Unauthenticated("missing credentials"))
+    }
+
+    fn authenticate_api_key(&self, api_key: &str) -> Result<Principal, AuthError> {
+        let hash: [u8; 32] = Sha256::digest(api_key.as_bytes()).into();
+
+        // Check every key without short-circuiting so timing does not reveal which matched
+        let mut matched = None;
+        for candidate in &self.api_keys {
+            let difference = candidate
+                .hash
+                .iter()
+                .zip(hash.iter())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+            if difference == 0 {
+                matched = Some(candidate);
+            }
+        }
+
+        matched
+            .map(|api_key| Principal {
+                subject: api_key.name.clone(),
+                method: AuthMethod::ApiKey,
+                roles: api_key.roles.clone(),
+                tier: api_key.tier.clone(),
+            })
+            .ok_or(AuthError::Unauthenticated("invalid API key"))
+    }
+
+    fn authenticate_jwt(&self, token: &str) -> Result<Principal, AuthError> {
+        let verifier = self.jwt.as_ref().ok_or(AuthError::Unauthenticated(
+            "JWT authentication is not enabled",
+        ))?;
+
+        let header = jsonwebtoken::decode_header(token)
+            .map_err(|_| AuthError::Unauthenticated("malformed JWT"))?;
+        let key = verifier
+            .keys
+            .iter()
+            .filter(|key| key.algorithm == header.alg)
+            .find(|key| header.kid.is_none() || key.kid == header.kid)
+            .ok_or(AuthError::Unauthenticated("no matching JWT key"))?;
+
+        let mut validation = verifier.validation.clone();
+        validation.algorithms = vec![key.algorithm];
+        let claims = jsonwebtoken::decode::<Claims>(token, &key.key, &validation)
+            .map_err(|e| {
+                tracing::debug!(error = %e, "JWT rejected");
+                AuthError::Unauthenticated("invalid JWT")
+            })?
+            .claims;
+
+        let mut roles = claims.roles;
+        if let Some(scope) = claims.scope {
+            roles.extend(scope.split_whitespace().map(str::to_string));
+        }
+
+        Ok(Principal {
+            subject: claims.sub,
+            method: AuthMethod::Jwt,
+            roles,
+            tier: claims.tier,
+        })
+    }
+
+    fn authorize(&self, path: &str, headers: &HeaderMap) -> Result<Option<Principal>, AuthError> {
+        let policy = self.policy_for(path);
+        if policy == AuthPolicy::Open {
+            return Ok(None);
+        }
+
+        let principal = self.authenticate(headers)?;
+        if policy == AuthPolicy::Admin && !principal.has_role(ADMIN_ROLE) {
+            return Err(AuthError::Forbidden);
+        }
+
+        Ok(Some(principal))
+    }
+}
+
+/// Keep the HMAC (`oct`) and RSA keys from a JWKS
+///
+/// A key's `alg` member decides its algorithm; keys without one are taken as
+/// HS256 (`oct`) or RS256 (`RSA`). Keys for encryption or other key types
+/// are skipped.
+fn load_jwt_keys(jwks: &JwkSet) -> Result<Vec<JwtKey>> {
+    let mut keys = Vec::new();
+    for jwk in &jwks.keys {
+        let kid = jwk.common.key_id.clone();
+        let (default, allowed): (Algorithm, &[Algorithm]) = match jwk.algorithm {
+            AlgorithmParameters::OctetKey(_) => (
+                Algorithm::HS256,
+                &[Algorithm::HS256, Algorithm::HS384, Algorithm::HS512],
+            ),
+            AlgorithmParameters::RSA(_) => (
+                Algorithm::RS256,
+                &[
+                    Algorithm::RS256,
+                    Algorithm::RS384,
+                    Algorithm::RS512,
+                    Algorithm::PS256,
+                    Algorithm::PS384,
+                    Algorithm::PS512,
+                ],
+            ),
+            _ => continue,
+        };
+        let algorithm = match &jwk.common.key_algorithm {
+            // Encryption algorithms have no signing counterpart
+            Some(alg) => match alg.to_string().parse::<Algorithm>() {
+                Ok(algorithm) => algorithm,
+                Err(_) => continue,
+            },
+            None => default,
+        };
+        if !allowed.contains(&algorithm) {
+            return Err(anyhow!(
+                "JWKS key {:?} has alg {:?}, which does not match its key type",
+                kid,
+                algorithm
+            ));
+        }
+
+        let key = DecodingKey::from_jwk(jwk).context("Invalid key in JWKS")?;
+        keys.push(JwtKey {
+            kid,
+            algorithm,
+            key,
+        });
+    }
+
+    if keys.is_empty() {
+        return Err(anyhow!("JWKS contains no HMAC or RSA signing keys"));
+    }
+    Ok(keys)
+}
+
+/// Authentication middleware enforcing the per-route policy
+///
+/// On success the caller's `Principal` is added to the request extensions.
+pub async fn auth_middleware(
+    State(authenticator): State<Arc<Authenticator>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+
+    match authenticator.authorize(&path, request.headers()) {
+        Ok(Some(principal)) => {
+            tracing::debug!(subject = %principal.subject, method = ?principal.method, "Request authenticated");
+            request.extensions_mut().insert(principal);
+            next.run(request).await
+        }
+        Ok(None) => next.run(request).await,
+        Err(e) => {
+            tracing::info!(path = %path, error = ?e, "Request rejected by authentication");
+            e.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ApiKeyConfig, JwtConfig};
+    use axum::http::HeaderValue;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    const HS256_SECRET: &[u8] = b"unit-test-secret";
+
+    fn authenticator(jwks: serde_json::Value) -> Authenticator {
+        let jwks_path = std::env::temp_dir().join(format!("jwks-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&jwks_path, jwks.to_string()).unwrap();
+
+        let config = AuthConfig {
+            enabled: true,
+            api_keys: vec![ApiKeyConfig {
+                name: "batch-client".to_string(),
+                key_sha256: format!("{:x}", Sha256::digest(b"secret-key")),
+                roles: vec![],
+                tier: None,
+            }],
+            jwt: Some(JwtConfig {
+                jwks_path: jwks_path.clone(),
+                issuer: Some("test-issuer".to_string()),
+                audience: None,
+                leeway_secs: 0,
+            }),
+            ..AuthConfig::default()
+        };
+
+        let authenticator = Authenticator::from_config(&config).unwrap();
+        std::fs::remove_file(jwks_path).unwrap();
+        authenticator
+    }
+
+    fn hs256_jwks() -> serde_json::Value {
+        use base64::Engine;
+        let k = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(HS256_SECRET);
+        json!({ "keys": [{ "kty": "oct", "kid": "hs", "alg": "HS256", "k": k }] })
+    }
+
+    fn hs256_token(claims: serde_json::Value) -> String {
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("hs".to_string());
+        jsonwebtoken::encode(&header, &claims, &EncodingKey::from_secret(HS256_SECRET)).unwrap()
+    }
+
+    fn headers(name: &'static str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_route_policies() {
+        let auth = authenticator(hs256_jwks());
+
+        assert_eq!(auth.policy_for("/health"), AuthPolicy::Open);
+        assert_eq!(auth.policy_for("/docs/index.html"), AuthPolicy::Open);
+        assert_eq!(auth.policy_for("/predict"), AuthPolicy::Authenticated);
+        assert_eq!(auth.policy_for("/admin/log-level"), AuthPolicy::Admin);
+        // Prefixes match whole path segments only
+        assert_eq!(auth.policy_for("/healthz"), AuthPolicy::Authenticated);
+    }
+
+    #[test]
+    fn test_api_key() {
+        let auth = authenticator(hs256_jwks());
+
+        let principal = auth
+            .authorize("/predict", &headers("x-api-key", "secret-key"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(principal.subject, "batch-client");
+        assert_eq!(principal.method, AuthMethod::ApiKey);
+
+        assert!(auth
+            .authorize("/predict", &headers("x-api-key", "wrong-key"))
+            .is_err());
+        assert!(auth.authorize("/predict", &HeaderMap::new()).is_err());
+        assert!(auth
+            .authorize("/health", &HeaderMap::new())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_hs256_jwt() {
+        let auth = authenticator(hs256_jwks());
+        let exp = chrono::Utc::now().timestamp() + 60;
+
+        let token = hs256_token(
+            json!({ "sub": "alice", "iss": "test-issuer", "exp": exp, "scope": "admin" }),
+        );
+        let principal = auth
+            .authorize(
+                "/admin/log-level",
+                &headers("authorization", &format!("Bearer {token}")),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(principal.subject, "alice");
+        assert!(principal.has_role("admin"));
+
+        // Wrong issuer
+        let token = hs256_token(json!({ "sub": "alice", "iss": "other", "exp": exp }));
+        assert!(auth
+            .authorize(
+                "/predict",
+                &headers("authorization", &format!("Bearer {token}"))
+            )
+            .is_err());
+
+        // Expired
+        let token = hs256_token(json!({ "sub": "alice", "iss": "test-issuer", "exp": exp - 120 }));
+        assert!(auth
+            .authorize(
+                "/predict",
+                &headers("authorization", &format!("Bearer {token}"))
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_invalid_key_hash_is_an_error() {
+        // Not validated first: from_config must not panic on bad input
+        for key_sha256 in ["abc", "é".repeat(32).as_str(), &"zz".repeat(32)] {
+            let config = AuthConfig {
+                enabled: true,
+                api_keys: vec![ApiKeyConfig {
+                    name: "batch-client".to_string(),
+                    key_sha256: key_sha256.to_string(),
+                    roles: vec![],
+                    tier: None,
+                }],
+                ..AuthConfig::default()
+            };
+            assert!(Authenticator::from_config(&config).is_err());
+        }
+    }
+
+    #[test]
+    fn test_jwk_alg_selects_algorithm() {
+        use base64::Engine;
+        let k = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(HS256_SECRET);
+        let auth = authenticator(json!({ "keys": [
+            { "kty": "oct", "kid": "hs512", "alg": "HS512", "k": k },
+            { "kty": "oct", "kid": "legacy", "k": k }
+        ] }));
+        let exp = chrono::Utc::now().timestamp() + 60;
+        let claims = json!({ "sub": "alice", "iss": "test-issuer", "exp": exp });
+        let token = |algorithm, kid: &str| {
+            let mut header = Header::new(algorithm);
+            header.kid = Some(kid.to_string());
+            let token =
+                jsonwebtoken::encode(&header, &claims, &EncodingKey::from_secret(HS256_SECRET))
+                    .unwrap();
+            headers("authorization", &format!("Bearer {token}"))
+        };
+
+        assert!(auth
+            .authorize("/predict", &token(Algorithm::HS512, "hs512"))
+            .is_ok());
+        // Keys without `alg` fall back to the key type's default
+        assert!(auth
+            .authorize("/predict", &token(Algorithm::HS256, "legacy"))
+            .is_ok());
+        assert!(auth
+            .authorize("/predict", &token(Algorithm::HS256, "hs512"))
+            .is_err());
+
+        let jwks = json!({ "keys": [{ "kty": "oct", "alg": "RS256", "k": k }] });
+        let jwks = serde_json::from_value(jwks).unwrap();
+        assert!(load_jwt_keys(&jwks).is_err());
+    }
+
+    #[test]
+    fn test_admin_requires_role() {
+        let auth = authenticator(hs256_jwks());
+        let exp = chrono::Utc::now().timestamp() + 60;
+
+        let token = hs256_token(json!({ "sub": "bob", "iss": "test-issuer", "exp": exp }));
+        let result = auth.authorize(
+            "/admin/log-level",
+            &headers("authorization", &format!("Bearer {token}")),
+        );
+        assert_eq!(result.unwrap_err(), AuthError::Forbidden);
+    }
+}
@@ -0,0 +1,432 @@
+use crate::app::{self, AppState};
+use crate::config::Config;
+use crate::models::ml_model;
+use crate::replay::LatencySummary;
+use anyhow::{bail, Context, Result};
+use axum::{body::Body, http::Request, Router};
+use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tower::ServiceExt;
+
+/// Generate synthetic prediction load and report latency and throughput
+#[derive(Debug, Clone, Args)]
+pub struct BenchArgs {
+    /// Base URL of a running service; defaults to the in-process router
+    #[arg(long)]
+    pub url: Option<String>,
+
+    /// Requests in flight at once
+    #[arg(long, default_value_t = 16)]
+    pub concurrency: usize,
+
+    /// Target request rate across all workers; unlimited when unset
+    #[arg(long)]
+    pub rps: Option<f64>,
+
+    /// How long to generate load
+    #[arg(long, default_value_t = 10)]
+    pub duration_secs: u64,
+
+    /// Stop after this many requests, even if time remains
+    #[arg(long)]
+    pub requests: Option<u64>,
+
+    /// Features per request; read from the model metadata when unset
+    #[arg(long)]
+    pub features: Option<usize>,
+
+    /// API key sent as `X-API-Key`
+    #[arg(long, env = "AI_MODEL_SERVICE_API_KEY")]
+    pub api_key: Option<String>,
+
+    /// Seed for the synthetic feature values
+    #[arg(long, default_value_t = 1)]
+    pub seed: u64,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    pub target: String,
+    pub concurrency: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_rps: Option<f64>,
+    pub feature_count: usize,
+    pub elapsed_secs: f64,
+    pub requests: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub error_rate: f64,
+    pub throughput_rps: f64,
+    /// Latency of successful requests
+    pub latency: LatencySummary,
+    /// Responses by HTTP status, plus `transport_error` for failed connections
+    pub outcomes: BTreeMap<String, usize>,
+}
+
+impl BenchReport {
+    fn print(&self) {
+        println!(
+            "Benchmarked {} for {:.1}s with concurrency {}{}",
+            self.target,
+            self.elapsed_secs,
+            self.concurrency,
+            self.target_rps
+                .map(|rps| format!(" at a target of {} req/s", rps))
+                .unwrap_or_default()
+        );
+        println!(
+            "  requests: {} ({} ok, {} failed, error rate {:.2}%)",
+            self.requests,
+            self.succeeded,
+            self.failed,
+            self.error_rate * 100.0
+        );
+        println!("  throughput: {:.1} req/s", self.throughput_rps);
+        println!(
+            "  latency ms: p50 {:.3}, p95 {:.3}, p99 {:.3}, max {:.3}",
+            self.latency.p50_ms, self.latency.p95_ms, self.latency.p99_ms, self.latency.max_ms
+        );
+        let outcomes: Vec<String> = self
+            .outcomes
+            .iter()
+            .map(|(outcome, count)| format!("{} x{}", outcome, count))
+            .collect();
+        println!("  outcomes: {}", outcomes.join(", "));
+    }
+}
+
+enum Target {
+    InProcess(Router),
+    Remote {
+        client: reqwest::Client,
+        url: String,
+    },
+}
+
+impl Target {
+    fn describe(&self) -> String {
+        match self {
+            Target::InProcess(_) => "in-process router".to_string(),
+            Target::Remote { url, .. } => url.clone(),
+        }
+    }
+
+    /// Send one prediction; `Err` means the request never got a response
+    async fn send(&self, body: String, api_key: Option<&str>) -> Result<u16, String> {
+        match self {
+            Target::InProcess(router) => {
+                let mut request = Request::builder()
+                    .method("POST")
+                    .uri("/predict")
+                    .header("content-type", "application/json");
+                if let Some(api_key) = api_key {
+                    request = request.header("x-api-key", api_key);
+                }
+                let request = request.body(Body::from(body)).map_err(|e| e.to_string())?;
+
+                let response = router
+                    .clone()
+                    .oneshot(request)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let status = response.status().as_u16();
+                axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(status)
+            }
+            Target::Remote { client, url } => {
+                let mut request = client
+                    .post(format!("{}/predict", url))
+                    .header("content-type", "application/json")
+                    .body(body);
+                if let Some(api_key) = api_key {
+                    request = request.header("x-api-key", api_key);
+                }
+
+                let response = request.send().await.map_err(|e| e.to_string())?;
+                let status = response.status().as_u16();
+                response.bytes().await.map_err(|e| e.to_string())?;
+                Ok(status)
+            }
+        }
+    }
+}
+
+/// Deterministic feature generator (xorshift64*), one per worker
+struct FeatureGenerator(u64);
+
+impl FeatureGenerator {
+    fn new(seed: u64) -> Self {
+        // xorshift must not start at zero
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        let bits = self.0.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11;
+        bits as f64 / (1u64 << 53) as f64
+    }
+
+    /// Values uniformly distributed in [-10, 10)
+    fn features(&mut self, count: usize) -> Vec<f64> {
+        (0..count).map(|_| self.next_f64() * 20.0 - 10.0).collect()
+    }
+}
+
+/// Shared schedule handing out request slots to workers
+struct Schedule {
+    issued: AtomicU64,
+    start: Instant,
+    deadline: Instant,
+    max_requests: Option<u64>,
+    rps: Option<f64>,
+}
+
+impl Schedule {
+    /// Wait for the next slot and return when it was due, or `None` once the
+    /// run is over
+    async fn next(&self) -> Option<Instant> {
+        let slot = self.issued.fetch_add(1, Ordering::Relaxed);
+        if self.max_requests.is_some_and(|max| slot >= max) {
+            return None;
+        }
+
+        let due = match self.rps {
+            Some(rps) => self.start + Duration::from_secs_f64(slot as f64 / rps),
+            None => Instant::now(),
+        };
+        if due >= self.deadline {
+            return None;
+        }
+        tokio::time::sleep_until(due).await;
+        Some(due)
+    }
+}
+
+/// Run the benchmark described by `args`
+///
+/// `config` configures the in-process router and is unused for a URL target.
+pub async fn bench(args: &BenchArgs, config: Config) -> Result<BenchReport> {
+    if args.concurrency == 0 {
+        bail!("--concurrency must be greater than 0");
+    }
+    if args.rps.is_some_and(|rps| rps <= 0.0 || !rps.is_finite()) {
+        bail!("--rps must be a positive number");
+    }
+
+    let (target, discovered_features) = match &args.url {
+        Some(url) => {
+            let url = url.trim_end_matches('/').to_string();
+            let client = reqwest::Client::new();
+            let features = match args.features {
+                Some(_) => None,
+                None => Some(remote_feature_count(&client, &url).await?),
+            };
+            (Target::Remote { client, url }, features)
+        }
+        None => {
+            let model = ml_model::load_model(config.model.path.as_deref())?;
+            ml_model::warm_up()?;
+            let state = AppState::new(config)?;
+            state.readiness.set_model_ready();
+            (
+                Target::InProcess(app::create_router(state)),
+                Some(model.metadata().feature_count),
+            )
+        }
+    };
+    let feature_count = args
+        .features
+        .or(discovered_features)
+        .context("feature count unknown")?;
+
+    let target = Arc::new(target);
+    let start = Instant::now();
+    let schedule = Arc::new(Schedule {
+        issued: AtomicU64::new(0),
+        start,
+        deadline: start + Duration::from_secs(args.duration_secs),
+        max_requests: args.requests,
+        rps: args.rps,
+    });
+
+    let mut workers = tokio::task::JoinSet::new();
+    for worker in 0..args.concurrency {
+        let target = target.clone();
+        let schedule = schedule.clone();
+        let api_key = args.api_key.clone();
+        let mut generator = FeatureGenerator::new(args.seed.wrapping_add(worker as u64));
+
+        workers.spawn(async move {
+            let mut results = Vec::new();
+            while let Some(due) = schedule.next().await {
+                let body = serde_json::json!({ "features": generator.features(feature_count) })
+                    .to_string();
+                let outcome = target.send(body, api_key.as_deref()).await;
+                // Measured from when the request was due, so a saturated
+                // target shows up as latency rather than a lower send rate
+                results.push((due.elapsed(), outcome));
+            }
+            results
+        });
+    }
+
+    let mut latencies = Vec::new();
+    let mut outcomes = BTreeMap::new();
+    let mut requests = 0;
+    let mut succeeded = 0;
+    while let Some(results) = workers.join_next().await {
+        for (latency, outcome) in results? {
+            requests += 1;
+            let key = match outcome {
+                Ok(status) => {
+                    if (200..300).contains(&status) {
+                        succeeded += 1;
+                        latencies.push(latency);
+                    }
+                    status.to_string()
+                }
+                Err(e) => {
+                    tracing::debug!(error = %e, "Benchmark request failed");
+                    "transport_error".to_string()
+                }
+            };
+            *outcomes.entry(key).or_insert(0) += 1;
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let failed = requests - succeeded;
+    Ok(BenchReport {
+        target: target.describe(),
+        concurrency: args.concurrency,
+        target_rps: args.rps,
+        feature_count,
+        elapsed_secs: elapsed,
+        requests,
+        succeeded,
+        failed,
+        error_rate: if requests > 0 {
+            failed as f64 / requests as f64
+        } else {
+            0.0
+        },
+        throughput_rps: requests as f64 / elapsed,
+        latency: LatencySummary::from_samples(&latencies),
+        outcomes,
+    })
+}
+
+/// Entry point for the `bench` subcommand
+pub async fn run(args: BenchArgs, config: Config) -> Result<()> {
+    let report = bench(&args, config).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        report.print();
+    }
+    Ok(())
+}
+
+/// Feature count of the first model listed by `GET /models`
+async fn remote_feature_count(client: &reqwest::Client, url: &str) -> Result<usize> {
+    let models: serde_json::Value = client
+        .get(format!("{}/models", url))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| {
+            format!(
+                "Failed to read model metadata from {}; pass --features",
+                url
+            )
+        })?
+        .json()
+        .await?;
+
+    models["models"][0]["feature_count"]
+        .as_u64()
+        .map(|count| count as usize)
+        .context("Model metadata has no feature_count; pass --features")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> BenchArgs {
+        BenchArgs {
+            url: None,
+            concurrency: 4,
+            rps: None,
+            duration_secs: 5,
+            requests: Some(200),
+            features: None,
+            api_key: None,
+            seed: 1,
+            json: false,
+        }
+    }
+
+    #[test]
+    fn test_feature_generator() {
+        let features = FeatureGenerator::new(7).features(1000);
+        assert!(features.iter().all(|f| (-10.0..10.0).contains(f)));
+        assert_eq!(features, FeatureGenerator::new(7).features(1000));
+        assert_ne!(features, FeatureGenerator::new(8).features(1000));
+    }
+
+    #[tokio::test]
+    async fn test_bench_in_process() {
+        let report = bench(&args(), Config::default()).await.unwrap();
+
+        assert_eq!(report.requests, 200);
+        assert_eq!(report.succeeded, 200);
+        assert_eq!(report.error_rate, 0.0);
+        assert_eq!(report.feature_count, 10);
+        assert_eq!(report.outcomes["200"], 200);
+        assert!(report.throughput_rps > 0.0);
+        assert_eq!(report.latency.count, 200);
+    }
+
+    #[tokio::test]
+    async fn test_bench_counts_errors() {
+        let args = BenchArgs {
+            features: Some(3),
+            requests: Some(20),
+            ..args()
+        };
+        let report = bench(&args, Config::default()).await.unwrap();
+
+        assert_eq!(report.failed, 20);
+        assert_eq!(report.error_rate, 1.0);
+        assert_eq!(report.outcomes["400"], 20);
+    }
+
+    #[tokio::test]
+    async fn test_bench_respects_rps() {
+        let args = BenchArgs {
+            rps: Some(100.0),
+            requests: None,
+            duration_secs: 1,
+            ..args()
+        };
+        let report = bench(&args, Config::default()).await.unwrap();
+
+        // 100 slots fall within the one-second window
+        assert_eq!(report.requests, 100);
+    }
+}
@@ -0,0 +1,1200 @@
+use crate::bench::BenchArgs;
+use crate::replay::ReplayArgs;
+use anyhow::{bail, Context, Result};
+use axum::http::HeaderValue;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Command-line flags; each flag can also be set through its environment variable
+///
+/// Precedence, highest first: CLI flag, environment variable, config file, default.
+/// Without a subcommand the service is started.
+#[derive(Debug, Default, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Path to a TOML configuration file
+    #[arg(long, short, env = "AI_MODEL_SERVICE_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Address to listen on, e.g. 0.0.0.0:3000
+    #[arg(long, env = "AI_MODEL_SERVICE_BIND_ADDRESS")]
+    pub bind_address: Option<SocketAddr>,
+
+    /// Service name reported by the health endpoint
+    #[arg(long, env = "AI_MODEL_SERVICE_NAME")]
+    pub service_name: Option<String>,
+
+    /// Tracing filter directives, e.g. "ai_model_service=info"
+    #[arg(long, env = "RUST_LOG")]
+    pub log_filter: Option<String>,
+
+    /// Log line format
+    #[arg(long, env = "AI_MODEL_SERVICE_LOG_FORMAT", value_enum)]
+    pub log_format: Option<LogFormat>,
+
+    /// Write logs to this file, rotated by size, instead of stdout
+    #[arg(long, env = "AI_MODEL_SERVICE_LOG_FILE")]
+    pub log_file: Option<PathBuf>,
+
+    /// Path to a JSON model artifact
+    #[arg(long, env = "AI_MODEL_SERVICE_MODEL_PATH")]
+    pub model_path: Option<PathBuf>,
+
+    /// Comma-separated list of allowed CORS origins, or "*"
+    #[arg(long, env = "AI_MODEL_SERVICE_CORS_ORIGINS", value_delimiter = ',')]
+    pub cors_origins: Option<Vec<String>>,
+
+    /// Maximum accepted request body size in bytes
+    #[arg(long, env = "AI_MODEL_SERVICE_MAX_BODY_BYTES")]
+    pub max_body_bytes: Option<usize>,
+
+    /// Maximum time to handle a request, in seconds
+    #[arg(long, env = "AI_MODEL_SERVICE_REQUEST_TIMEOUT_SECS")]
+    pub request_timeout_secs: Option<u64>,
+}
+
+/// Offline tools run instead of the server
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Replay recorded predictions and report latency and output differences
+    Replay(ReplayArgs),
+    /// Generate synthetic prediction load and report latency and throughput
+    Bench(BenchArgs),
+}
+
+/// Typed service configuration
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub model: ModelConfig,
+    pub logging: LoggingConfig,
+    pub cors: CorsConfig,
+    pub limits: LimitsConfig,
+    pub timeouts: TimeoutsConfig,
+    pub readiness: ReadinessConfig,
+    pub shutdown: ShutdownConfig,
+    pub tls: TlsConfig,
+    pub auth: AuthConfig,
+    pub rate_limit: RateLimitConfig,
+    pub telemetry: TelemetryConfig,
+    pub audit: AuditConfig,
+    pub metrics: MetricsConfig,
+    pub drift: DriftConfig,
+    pub output_drift: OutputDriftConfig,
+    pub ood: OodConfig,
+    pub slo: SloConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub bind_address: SocketAddr,
+    pub service_name: String,
+    pub log_filter: String,
+}
+
+/// Log format and sink; the filter itself is `server.log_filter`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    pub format: LogFormat,
+    /// Log file; logs go to stdout when unset
+    pub file_path: Option<PathBuf>,
+    /// Size at which the log file is rotated
+    pub max_file_bytes: u64,
+    /// Rotated files kept alongside the active one (`<file>.1` is the newest)
+    pub max_files: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModelConfig {
+    /// JSON model artifact; the built-in model is used when unset
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    /// Allowed origins; `["*"]` allows any origin
+    pub allowed_origins: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsConfig {
+    pub max_body_bytes: usize,
+    /// Requests handled at once; further requests wait in a queue
+    pub max_concurrent_requests: usize,
+    /// Requests allowed to wait for a slot; beyond this they get 503
+    pub max_queue_length: usize,
+    /// Longest a request may wait in the queue before it gets 503
+    pub queue_timeout_ms: u64,
+    /// Shed requests that would queue while the recent average queue delay
+    /// exceeds this threshold
+    pub shed_queue_delay_ms: u64,
+    /// Path prefixes exempt from the concurrency limit and load shedding
+    pub exempt_prefixes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeoutsConfig {
+    /// Default time allowed to handle a request
+    pub request_secs: u64,
+    /// Per-route overrides; the longest matching path prefix wins
+    pub routes: Vec<RouteTimeoutConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouteTimeoutConfig {
+    pub prefix: String,
+    pub timeout_ms: u64,
+}
+
+/// Shutdown sequencing on SIGTERM/SIGINT
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShutdownConfig {
+    /// Time readiness fails before the listener stops accepting connections,
+    /// so load balancers can deregister the instance
+    pub drain_delay_secs: u64,
+    /// Maximum time to wait for in-flight requests after the listener closes
+    pub grace_period_secs: u64,
+}
+
+/// OpenTelemetry trace export over OTLP/HTTP
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// Collector traces endpoint (OTLP/HTTP protobuf)
+    pub otlp_endpoint: String,
+    /// Fraction of new traces to sample; incoming `traceparent` decisions are honoured
+    pub sample_ratio: f64,
+    pub export_timeout_ms: u64,
+}
+
+/// Prometheus metric options
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// Buckets for `http_request_duration_seconds`, in seconds
+    pub http_request_duration_seconds: HistogramConfig,
+    /// Buckets for `ml_prediction_confidence`; spans the model's confidence
+    /// range when unset
+    pub ml_prediction_confidence: HistogramConfig,
+    /// Buckets for `ml_prediction_value`; symmetric around zero when unset
+    pub ml_prediction_value: HistogramConfig,
+}
+
+/// Histogram bucket upper bounds, given explicitly or generated; at most one
+/// of the two may be set
+///
+/// Only classic histograms are supported: the `prometheus` client cannot
+/// encode native (sparse) histograms, so there is no option for them.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistogramConfig {
+    /// Strictly increasing upper bounds
+    pub buckets: Option<Vec<f64>>,
+    /// `count` bounds starting at `start`, each `factor` times the previous
+    pub exponential: Option<ExponentialBucketsConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExponentialBucketsConfig {
+    pub start: f64,
+    pub factor: f64,
+    pub count: usize,
+}
+
+impl HistogramConfig {
+    /// Configured bucket bounds, or `None` to use the metric's default
+    pub fn bucket_bounds(&self) -> Option<Vec<f64>> {
+        if let Some(buckets) = &self.buckets {
+            return Some(buckets.clone());
+        }
+        self.exponential.as_ref().map(|exponential| {
+            (0..exponential.count)
+                .map(|i| exponential.start * exponential.factor.powi(i as i32))
+                .collect()
+        })
+    }
+
+    fn validate(&self, name: &str, errors: &mut Vec<String>) {
+        let error_count = errors.len();
+        if self.buckets.is_some() && self.exponential.is_some() {
+            errors.push(format!(
+                "metrics.{}: set either buckets or exponential, not both",
+                name
+            ));
+            return;
+        }
+        if let Some(exponential) = &self.exponential {
+            if !(exponential.start.is_finite() && exponential.start > 0.0) {
+                errors.push(format!(
+                    "metrics.{}.exponential.start must be greater than 0",
+                    name
+                ));
+            }
+            if !(exponential.factor.is_finite() && exponential.factor > 1.0) {
+                errors.push(format!(
+                    "metrics.{}.exponential.factor must be greater than 1",
+                    name
+                ));
+            }
+            if exponential.count == 0 {
+                errors.push(format!(
+                    "metrics.{}.exponential.count must be greater than 0",
+                    name
+                ));
+            }
+            if errors.len() > error_count {
+                return;
+            }
+        }
+        let Some(bounds) = self.bucket_bounds() else {
+            return;
+        };
+        // Generated bounds can overflow to infinity or stop increasing once
+        // rounding catches up with the factor
+        let field = if self.buckets.is_some() {
+            "buckets"
+        } else {
+            "exponential"
+        };
+        if bounds.is_empty() {
+            errors.push(format!("metrics.{}.{} must not be empty", name, field));
+        } else if bounds.iter().any(|bound| !bound.is_finite()) {
+            errors.push(format!("metrics.{}.{} must be finite", name, field));
+        } else if bounds.windows(2).any(|pair| pair[0] >= pair[1]) {
+            errors.push(format!(
+                "metrics.{}.{} must be strictly increasing",
+                name, field
+            ));
+        }
+    }
+}
+
+/// Input drift detection against the model's reference profile
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DriftConfig {
+    pub enabled: bool,
+    /// Predictions per window; statistics restart when a window fills
+    pub window_size: u64,
+    /// Samples a window needs before drift scores are computed from it
+    pub min_samples: u64,
+    /// Population stability index above which a feature counts as drifted
+    pub psi_threshold: f64,
+    /// Kolmogorov-Smirnov statistic above which a feature counts as drifted
+    pub ks_threshold: f64,
+}
+
+/// Shift detection on predicted values against a baseline window
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputDriftConfig {
+    pub enabled: bool,
+    /// Predictions per window; the first full window of a model version
+    /// becomes its baseline
+    pub window_size: u64,
+    /// Samples a window needs before it is compared to the baseline
+    pub min_samples: u64,
+    /// Mean shift, in baseline standard deviations, that counts as a shift
+    pub mean_shift_threshold: f64,
+    /// Largest shift of any tracked quantile, in baseline standard
+    /// deviations, that counts as a shift
+    pub quantile_shift_threshold: f64,
+    /// Ratio of standard deviations (either way round) that counts as a shift
+    pub std_dev_ratio_threshold: f64,
+}
+
+/// Per-request check for inputs far outside the training data, using the
+/// model's reference profile
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OodConfig {
+    pub enabled: bool,
+    /// Largest per-feature absolute z-score still considered in distribution
+    pub z_threshold: f64,
+    /// Reject flagged requests with 400 instead of only counting them
+    pub reject: bool,
+}
+
+/// Availability and latency objectives for `/predict`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SloConfig {
+    pub enabled: bool,
+    /// Target share of requests answered without a 5xx or a timeout
+    pub availability_target: f64,
+    /// Target share of requests answered within `latency_threshold_ms`
+    pub latency_target: f64,
+    pub latency_threshold_ms: u64,
+}
+
+/// Prediction audit log written as NDJSON to local disk
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuditConfig {
+    pub enabled: bool,
+    /// Active audit file; rotated files get `.1`, `.2`, ... suffixes
+    pub path: PathBuf,
+    pub max_file_bytes: u64,
+    /// Rotated files kept; every rotated file is kept when unset, so no
+    /// prediction record is ever deleted
+    pub max_files: Option<usize>,
+    /// Records buffered in memory before new ones are dropped
+    pub buffer_size: usize,
+    /// Fraction of predictions recorded, chosen by request ID
+    pub sample_rate: f64,
+}
+
+/// Native TLS termination on the listener
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TlsConfig {
+    pub enabled: bool,
+    /// PEM certificate chain
+    pub cert_path: Option<PathBuf>,
+    /// PEM private key
+    pub key_path: Option<PathBuf>,
+    /// PEM CA bundle; when set, clients must present a certificate it signed (mTLS)
+    pub client_ca_path: Option<PathBuf>,
+    /// How often to check the certificate files for changes
+    pub reload_interval_secs: u64,
+}
+
+/// Authentication of API clients
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    pub enabled: bool,
+    /// Static API keys, accepted in the `X-API-Key` header
+    pub api_keys: Vec<ApiKeyConfig>,
+    /// JWT bearer tokens, accepted in the `Authorization` header
+    pub jwt: Option<JwtConfig>,
+    /// Policy for routes not matched by any entry in `routes`
+    pub default_policy: AuthPolicy,
+    /// Per-route policies; the longest matching path prefix wins
+    pub routes: Vec<RoutePolicyConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyConfig {
+    /// Client name used as the authenticated subject
+    pub name: String,
+    /// Hex-encoded SHA-256 of the key; the key itself is never stored
+    pub key_sha256: String,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Rate limit tier; `rate_limit.authenticated_tier` when unset
+    #[serde(default)]
+    pub tier: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JwtConfig {
+    /// Local JWKS file with the HS256 (`oct`) and RS256 (`RSA`) verification keys
+    pub jwks_path: PathBuf,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    #[serde(default = "default_jwt_leeway_secs")]
+    pub leeway_secs: u64,
+}
+
+fn default_jwt_leeway_secs() -> u64 {
+    30
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthPolicy {
+    /// No credentials required
+    Open,
+    /// Any valid API key or JWT
+    Authenticated,
+    /// Valid credentials carrying the `admin` role
+    Admin,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoutePolicyConfig {
+    pub prefix: String,
+    pub policy: AuthPolicy,
+}
+
+/// Token-bucket rate limiting in front of `/predict`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    /// Tier for requests without credentials, keyed by client IP
+    pub anonymous_tier: String,
+    /// Tier for authenticated callers that do not name one
+    pub authenticated_tier: String,
+    /// Use the last `X-Forwarded-For` address, the one appended by the proxy
+    /// in front of the service, as the client IP; only enable behind such a proxy
+    pub trust_forwarded_for: bool,
+    pub tiers: HashMap<String, RateLimitTierConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitTierConfig {
+    /// Bucket capacity: requests allowed in a burst
+    pub burst: u32,
+    /// Tokens added per second
+    pub refill_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReadinessConfig {
+    pub dependencies: Vec<DependencyConfig>,
+}
+
+/// A TCP dependency that must be reachable for the service to be ready
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DependencyConfig {
+    pub name: String,
+    /// `host:port` to connect to
+    pub address: String,
+    #[serde(default = "default_dependency_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_dependency_timeout_ms() -> u64 {
+    1000
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: SocketAddr::from(([0, 0, 0, 0], 3000)),
+            service_name: "ai-model-service-abhinaw".to_string(),
+            log_filter: "ai_model_service=debug,tower_http=debug".to_string(),
+        }
+    }
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec!["*".to_string()],
+        }
+    }
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 64 * 1024,
+            max_concurrent_requests: 256,
+            max_queue_length: 512,
+            queue_timeout_ms: 1000,
+            shed_queue_delay_ms: 250,
+            exempt_prefixes: ["/health", "/livez", "/readyz", "/metrics"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+impl Default for TimeoutsConfig {
+    fn default() -> Self {
+        Self {
+            request_secs: 30,
+            routes: Vec::new(),
+        }
+    }
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cert_path: None,
+            key_path: None,
+            client_ca_path: None,
+            reload_interval_secs: 30,
+        }
+    }
+}
+
+impl TlsConfig {
+    pub fn reload_interval(&self) -> Duration {
+        Duration::from_secs(self.reload_interval_secs)
+    }
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        // Probes, metrics and API docs stay open; everything else needs credentials
+        let open = |prefix: &str| RoutePolicyConfig {
+            prefix: prefix.to_string(),
+            policy: AuthPolicy::Open,
+        };
+
+        Self {
+            enabled: false,
+            api_keys: Vec::new(),
+            jwt: None,
+            default_policy: AuthPolicy::Authenticated,
+            routes: vec![
+                open("/health"),
+                open("/livez"),
+                open("/readyz"),
+                open("/metrics"),
+                open("/openapi.json"),
+                open("/docs"),
+                RoutePolicyConfig {
+                    prefix: "/admin".to_string(),
+                    policy: AuthPolicy::Admin,
+                },
+            ],
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            anonymous_tier: "anonymous".to_string(),
+            authenticated_tier: "standard".to_string(),
+            trust_forwarded_for: false,
+            tiers: HashMap::from([
+                (
+                    "anonymous".to_string(),
+                    RateLimitTierConfig {
+                        burst: 10,
+                        refill_per_sec: 5.0,
+                    },
+                ),
+                (
+                    "standard".to_string(),
+                    RateLimitTierConfig {
+                        burst: 50,
+                        refill_per_sec: 25.0,
+                    },
+                ),
+            ]),
+        }
+    }
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            drain_delay_secs: 5,
+            grace_period_secs: 20,
+        }
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::Text,
+            file_path: None,
+            max_file_bytes: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: PathBuf::from("audit/predictions.ndjson"),
+            max_file_bytes: 100 * 1024 * 1024,
+            max_files: None,
+            buffer_size: 10_000,
+            sample_rate: 1.0,
+        }
+    }
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_size: 10_000,
+            min_samples: 500,
+            psi_threshold: 0.2,
+            ks_threshold: 0.1,
+        }
+    }
+}
+
+impl Default for OutputDriftConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_size: 10_000,
+            min_samples: 500,
+            mean_shift_threshold: 0.5,
+            quantile_shift_threshold: 1.0,
+            std_dev_ratio_threshold: 2.0,
+        }
+    }
+}
+
+impl Default for OodConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            z_threshold: 4.0,
+            reject: false,
+        }
+    }
+}
+
+impl Default for SloConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            availability_target: 0.999,
+            latency_target: 0.99,
+            latency_threshold_ms: 250,
+        }
+    }
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            otlp_endpoint: "http://localhost:4318/v1/traces".to_string(),
+            sample_ratio: 1.0,
+            export_timeout_ms: 10_000,
+        }
+    }
+}
+
+impl Config {
+    /// Load configuration from the file named by `cli`, apply CLI/env overrides and validate
+    pub fn load(cli: &Cli) -> Result<Self> {
+        let mut config = match &cli.config {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+
+        config.apply_overrides(cli);
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Parse a TOML configuration file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+
+        toml::from_str(&contents).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    fn apply_overrides(&mut self, cli: &Cli) {
+        if let Some(bind_address) = cli.bind_address {
+            self.server.bind_address = bind_address;
+        }
+        if let Some(service_name) = &cli.service_name {
+            self.server.service_name = service_name.clone();
+        }
+        if let Some(log_filter) = &cli.log_filter {
+            self.server.log_filter = log_filter.clone();
+        }
+        if let Some(log_format) = cli.log_format {
+            self.logging.format = log_format;
+        }
+        if let Some(log_file) = &cli.log_file {
+            self.logging.file_path = Some(log_file.clone());
+        }
+        if let Some(model_path) = &cli.model_path {
+            self.model.path = Some(model_path.clone());
+        }
+        if let Some(cors_origins) = &cli.cors_origins {
+            self.cors.allowed_origins = cors_origins.clone();
+        }
+        if let Some(max_body_bytes) = cli.max_body_bytes {
+            self.limits.max_body_bytes = max_body_bytes;
+        }
+        if let Some(request_timeout_secs) = cli.request_timeout_secs {
+            self.timeouts.request_secs = request_timeout_secs;
+        }
+    }
+
+    /// Check the configuration, reporting every problem at once
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+
+        if self.server.service_name.trim().is_empty() {
+            errors.push("server.service_name must not be empty".to_string());
+        }
+        if let Err(e) = tracing_subscriber::EnvFilter::try_new(&self.server.log_filter) {
+            errors.push(format!("server.log_filter is invalid: {}", e));
+        }
+        if let Some(path) = &self.model.path {
+            if !path.is_file() {
+                errors.push(format!(
+                    "model.path {} does not exist or is not a file",
+                    path.display()
+                ));
+            }
+        }
+        if self.cors.allowed_origins.is_empty() {
+            errors.push(
+                "cors.allowed_origins must not be empty; use [\"*\"] to allow any origin"
+                    .to_string(),
+            );
+        }
+        if self.cors.allowed_origins.len() > 1 && self.cors.allowed_origins.iter().any(|o| o == "*")
+        {
+            errors.push(
+                "cors.allowed_origins cannot combine \"*\" with explicit origins".to_string(),
+            );
+        }
+        for origin in self.cors.allowed_origins.iter().filter(|o| *o != "*") {
+            if HeaderValue::from_str(origin).is_err()
+                || !(origin.starts_with("http://") || origin.starts_with("https://"))
+            {
+                errors.push(format!(
+                    "cors.allowed_origins entry {:?} must be an http(s) origin",
+                    origin
+                ));
+            }
+        }
+        if self.logging.file_path.is_some() && self.logging.max_file_bytes == 0 {
+            errors.push("logging.max_file_bytes must be greater than 0".to_string());
+        }
+        if self.logging.file_path.is_some() && self.logging.max_files == 0 {
+            errors.push("logging.max_files must be greater than 0".to_string());
+        }
+        if self.limits.max_body_bytes == 0 {
+            errors.push("limits.max_body_bytes must be greater than 0".to_string());
+        }
+        if self.limits.max_concurrent_requests == 0 {
+            errors.push("limits.max_concurrent_requests must be greater than 0".to_string());
+        }
+        if self.limits.queue_timeout_ms == 0 {
+            errors.push("limits.queue_timeout_ms must be greater than 0".to_string());
+        }
+        if self.timeouts.request_secs == 0 {
+            errors.push("timeouts.request_secs must be greater than 0".to_string());
+        }
+        for route in &self.timeouts.routes {
+            if !route.prefix.starts_with('/') {
+                errors.push(format!(
+                    "timeouts.routes prefix {:?} must start with '/'",
+                    route.prefix
+                ));
+            }
+            if route.timeout_ms == 0 {
+                errors.push(format!(
+                    "timeouts.routes {:?} timeout_ms must be greater than 0",
+                    route.prefix
+                ));
+            }
+        }
+        if self.shutdown.grace_period_secs == 0 {
+            errors.push("shutdown.grace_period_secs must be greater than 0".to_string());
+        }
+        if self.tls.enabled {
+            for (field, path) in [
+                ("tls.cert_path", &self.tls.cert_path),
+                ("tls.key_path", &self.tls.key_path),
+            ] {
+                match path {
+                    Some(path) if !path.is_file() => errors.push(format!(
+                        "{} {} does not exist or is not a file",
+                        field,
+                        path.display()
+                    )),
+                    Some(_) => {}
+                    None => errors.push(format!("{} is required when tls.enabled is true", field)),
+                }
+            }
+            if let Some(path) = &self.tls.client_ca_path {
+                if !path.is_file() {
+                    errors.push(format!(
+                        "tls.client_ca_path {} does not exist or is not a file",
+                        path.display()
+                    ));
+                }
+            }
+            if self.tls.reload_interval_secs == 0 {
+                errors.push("tls.reload_interval_secs must be greater than 0".to_string());
+            }
+        } else if self.tls.client_ca_path.is_some() {
+            errors.push("tls.client_ca_path requires tls.enabled = true".to_string());
+        }
+        if self.auth.enabled && self.auth.api_keys.is_empty() && self.auth.jwt.is_none() {
+            errors.push("auth.enabled requires auth.api_keys or auth.jwt".to_string());
+        }
+        for api_key in &self.auth.api_keys {
+            if api_key.key_sha256.len() != 64
+                || !api_key.key_sha256.chars().all(|c| c.is_ascii_hexdigit())
+            {
+                errors.push(format!(
+                    "auth.api_keys entry {:?} key_sha256 must be 64 hex characters",
+                    api_key.name
+                ));
+            }
+        }
+        if let Some(jwt) = &self.auth.jwt {
+            if !jwt.jwks_path.is_file() {
+                errors.push(format!(
+                    "auth.jwt.jwks_path {} does not exist or is not a file",
+                    jwt.jwks_path.display()
+                ));
+            }
+        }
+        for route in &self.auth.routes {
+            if !route.prefix.starts_with('/') {
+                errors.push(format!(
+                    "auth.routes prefix {:?} must start with '/'",
+                    route.prefix
+                ));
+            }
+        }
+        for (name, tier) in &self.rate_limit.tiers {
+            if tier.burst == 0 {
+                errors.push(format!(
+                    "rate_limit.tiers.{}.burst must be greater than 0",
+                    name
+                ));
+            }
+            if !(tier.refill_per_sec.is_finite() && tier.refill_per_sec > 0.0) {
+                errors.push(format!(
+                    "rate_limit.tiers.{}.refill_per_sec must be greater than 0",
+                    name
+                ));
+            }
+        }
+        let tier_references = [
+            ("rate_limit.anonymous_tier", &self.rate_limit.anonymous_tier),
+            (
+                "rate_limit.authenticated_tier",
+                &self.rate_limit.authenticated_tier,
+            ),
+        ]
+        .into_iter()
+        .chain(self.auth.api_keys.iter().filter_map(|api_key| {
+            api_key
+                .tier
+                .as_ref()
+                .map(|tier| ("auth.api_keys tier", tier))
+        }));
+        for (field, tier) in tier_references {
+            if self.rate_limit.enabled && !self.rate_limit.tiers.contains_key(tier) {
+                errors.push(format!(
+                    "{} {:?} is not defined in rate_limit.tiers",
+                    field, tier
+                ));
+            }
+        }
+        for dependency in &self.readiness.dependencies {
+            if dependency.name.trim().is_empty() {
+                errors.push("readiness.dependencies entries need a name".to_string());
+            }
+            if !dependency.address.contains(':') {
+                errors.push(format!(
+                    "readiness dependency {:?} address {:?} must be host:port",
+                    dependency.name, dependency.address
+                ));
+            }
+            if dependency.timeout_ms == 0 {
+                errors.push(format!(
+                    "readiness dependency {:?} timeout_ms must be greater than 0",
+                    dependency.name
+                ));
+            }
+        }
+
+        if self.telemetry.enabled {
+            if !(self.telemetry.otlp_endpoint.starts_with("http://")
+                || self.telemetry.otlp_endpoint.starts_with("https://"))
+            {
+                errors.push(format!(
+                    "telemetry.otlp_endpoint {:?} must be an http(s) URL",
+                    self.telemetry.otlp_endpoint
+                ));
+            }
+            if self.telemetry.export_timeout_ms == 0 {
+                errors.push("telemetry.export_timeout_ms must be greater than 0".to_string());
+            }
+        }
+        if !(0.0..=1.0).contains(&self.telemetry.sample_ratio) {
+            errors.push("telemetry.sample_ratio must be between 0 and 1".to_string());
+        }
+
+        if self.audit.enabled {
+            if self.audit.path.as_os_str().is_empty() {
+                errors.push("audit.path must not be empty".to_string());
+            }
+            if self.audit.max_file_bytes == 0 {
+                errors.push("audit.max_file_bytes must be greater than 0".to_string());
+            }
+            if self.audit.max_files == Some(0) {
+                errors.push("audit.max_files must be greater than 0".to_string());
+            }
+            if self.audit.buffer_size == 0 {
+                errors.push("audit.buffer_size must be greater than 0".to_string());
+            }
+        }
+        if !(0.0..=1.0).contains(&self.audit.sample_rate) {
+            errors.push("audit.sample_rate must be between 0 and 1".to_string());
+        }
+        if self.drift.enabled {
+            if self.drift.min_samples == 0 {
+                errors.push("drift.min_samples must be greater than 0".to_string());
+            }
+            if self.drift.window_size < self.drift.min_samples {
+                errors.push("drift.window_size must be at least drift.min_samples".to_string());
+            }
+            if !(self.drift.psi_threshold.is_finite() && self.drift.psi_threshold > 0.0) {
+                errors.push("drift.psi_threshold must be greater than 0".to_string());
+            }
+            if !(self.drift.ks_threshold > 0.0 && self.drift.ks_threshold <= 1.0) {
+                errors.push("drift.ks_threshold must be between 0 and 1".to_string());
+            }
+        }
+        if self.output_drift.enabled {
+            let output = &self.output_drift;
+            if output.min_samples == 0 {
+                errors.push("output_drift.min_samples must be greater than 0".to_string());
+            }
+            if output.window_size < output.min_samples {
+                errors.push(
+                    "output_drift.window_size must be at least output_drift.min_samples"
+                        .to_string(),
+                );
+            }
+            if !(output.mean_shift_threshold.is_finite() && output.mean_shift_threshold > 0.0) {
+                errors.push("output_drift.mean_shift_threshold must be greater than 0".to_string());
+            }
+            if !(output.quantile_shift_threshold.is_finite()
+                && output.quantile_shift_threshold > 0.0)
+            {
+                errors.push(
+                    "output_drift.quantile_shift_threshold must be greater than 0".to_string(),
+                );
+            }
+            if !(output.std_dev_ratio_threshold.is_finite() && output.std_dev_ratio_threshold > 1.0)
+            {
+                errors.push(
+                    "output_drift.std_dev_ratio_threshold must be greater than 1".to_string(),
+                );
+            }
+        }
+        if self.ood.enabled && !(self.ood.z_threshold.is_finite() && self.ood.z_threshold > 0.0) {
+            errors.push("ood.z_threshold must be greater than 0".to_string());
+        }
+        if self.slo.enabled {
+            for (name, target) in [
+                ("availability_target", self.slo.availability_target),
+                ("latency_target", self.slo.latency_target),
+            ] {
+                if !(target > 0.0 && target < 1.0) {
+                    errors.push(format!("slo.{} must be between 0 and 1 (exclusive)", name));
+                }
+            }
+            if self.slo.latency_threshold_ms == 0 {
+                errors.push("slo.latency_threshold_ms must be greater than 0".to_string());
+            }
+        }
+        self.metrics
+            .http_request_duration_seconds
+            .validate("http_request_duration_seconds", &mut errors);
+        self.metrics
+            .ml_prediction_confidence
+            .validate("ml_prediction_confidence", &mut errors);
+        self.metrics
+            .ml_prediction_value
+            .validate("ml_prediction_value", &mut errors);
+
+        if !errors.is_empty() {
+            bail!("invalid configuration:\n  - {}", errors.join("\n  - "));
+        }
+
+        Ok(())
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.timeouts.request_secs)
+    }
+
+    pub fn drain_delay(&self) -> Duration {
+        Duration::from_secs(self.shutdown.drain_delay_secs)
+    }
+
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.shutdown.grace_period_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        let config = Config::default();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.server.bind_address.port(), 3000);
+    }
+
+    #[test]
+    fn test_parse_toml() {
+        let config: Config = toml::from_str(
+            r#"
+            [server]
+            bind_address = "127.0.0.1:8080"
+
+            [cors]
+            allowed_origins = ["https://example.com"]
+
+            [[readiness.dependencies]]
+            name = "feature-store"
+            address = "feature-store:6379"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.server.bind_address.port(), 8080);
+        // Unset fields keep their defaults
+        assert_eq!(config.server.service_name, "ai-model-service-abhinaw");
+        assert_eq!(config.readiness.dependencies[0].timeout_ms, 1000);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_unknown_field_rejected() {
+        let result: Result<Config, _> = toml::from_str("[server]\nbind_addres = \"0.0.0.0:1\"");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_overrides_file() {
+        let mut config = Config::default();
+        let cli = Cli {
+            bind_address: Some("127.0.0.1:9000".parse().unwrap()),
+            cors_origins: Some(vec!["https://a.example".to_string()]),
+            ..Cli::default()
+        };
+
+        config.apply_overrides(&cli);
+
+        assert_eq!(config.server.bind_address.port(), 9000);
+        assert_eq!(config.cors.allowed_origins, vec!["https://a.example"]);
+    }
+
+    #[test]
+    fn test_validation_reports_all_errors() {
+        let mut config = Config::default();
+        config.limits.max_body_bytes = 0;
+        config.timeouts.request_secs = 0;
+        config.cors.allowed_origins = vec!["*".to_string(), "example.com".to_string()];
+
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("limits.max_body_bytes"));
+        assert!(message.contains("timeouts.request_secs"));
+        assert!(message.contains("cannot combine"));
+        assert!(message.contains("example.com"));
+    }
+
+    #[test]
+    fn test_rotation_keeps_at_least_one_file() {
+        let mut config = Config::default();
+        config.logging.file_path = Some(PathBuf::from("service.log"));
+        config.logging.max_files = 0;
+        config.audit.enabled = true;
+        config.audit.max_files = Some(0);
+
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("logging.max_files must be greater than 0"));
+        assert!(message.contains("audit.max_files must be greater than 0"));
+
+        // Audit files are never deleted by default
+        assert_eq!(AuditConfig::default().max_files, None);
+    }
+
+    #[test]
+    fn test_tls_requires_certificate_paths() {
+        let mut config = Config::default();
+        config.tls.enabled = true;
+
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("tls.cert_path is required"));
+        assert!(message.contains("tls.key_path is required"));
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        let config: Config = toml::from_str(
+            r#"
+            [metrics.http_request_duration_seconds]
+            exponential = { start = 0.001, factor = 2.0, count = 4 }
+
+            [metrics.ml_prediction_confidence]
+            buckets = [0.1, 0.5, 0.9]
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.metrics.http_request_duration_seconds.bucket_bounds(),
+            Some(vec![0.001, 0.002, 0.004, 0.008])
+        );
+        assert_eq!(
+            config.metrics.ml_prediction_confidence.bucket_bounds(),
+            Some(vec![0.1, 0.5, 0.9])
+        );
+        assert_eq!(HistogramConfig::default().bucket_bounds(), None);
+
+        let mut config = Config::default();
+        config.metrics.ml_prediction_confidence.buckets = Some(vec![0.5, 0.5]);
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message
+            .contains("metrics.ml_prediction_confidence.buckets must be strictly increasing"));
+
+        let mut config = Config::default();
+        config.metrics.ml_prediction_value.exponential = Some(ExponentialBucketsConfig {
+            start: 1.0,
+            factor: 10.0,
+            count: 400,
+        });
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("metrics.ml_prediction_value.exponential must be finite"));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::app::AppState;
+use axum::{extract::State, http::StatusCode, response::Json};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Active log filter in `tracing` `EnvFilter` syntax
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LogLevel {
+    #[schema(example = "info,ai_model_service=debug")]
+    pub filter: String,
+}
+
+/// Current log level endpoint
+#[utoipa::path(
+    get,
+    path = "/admin/log-level",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Active log filter", body = LogLevel),
+        (status = 401, description = "Missing or invalid credentials (when authentication is enabled)"),
+        (status = 403, description = "Caller lacks the admin role, or authentication is disabled"),
+        (status = 503, description = "Runtime log level changes are not available")
+    )
+)]
+pub async fn get_log_level(State(state): State<AppState>) -> Result<Json<LogLevel>, StatusCode> {
+    require_authentication(&state).map_err(|(status, _)| status)?;
+    let handle = state
+        .log_level
+        .as_ref()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+    Ok(Json(LogLevel {
+        filter: handle.current(),
+    }))
+}
+
+/// Change log level endpoint
+///
+/// Replaces the active log filter without restarting; reverts on restart
+#[utoipa::path(
+    put,
+    path = "/admin/log-level",
+    tag = "admin",
+    request_body = LogLevel,
+    responses(
+        (status = 200, description = "Filter applied", body = LogLevel),
+        (status = 400, description = "Invalid filter directives"),
+        (status = 401, description = "Missing or invalid credentials (when authentication is enabled)"),
+        (status = 403, description = "Caller lacks the admin role, or authentication is disabled"),
+        (status = 422, description = "Request body does not match the schema"),
+        (status = 503, description = "Runtime log level changes are not available")
+    )
+)]
+pub async fn set_log_level(
+    State(state): State<AppState>,
+    Json(request): Json<LogLevel>,
+) -> Result<Json<LogLevel>, (StatusCode, String)> {
+    require_authentication(&state)?;
+    let handle = state.log_level.as_ref().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "runtime log level changes are not available".to_string(),
+    ))?;
+
+    let previous = handle.current();
+    handle
+        .set(&request.filter)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("{:#}", e)))?;
+
+    tracing::warn!(previous = %previous, filter = %request.filter, "Log filter changed");
+    Ok(Json(LogLevel {
+        filter: request.filter,
+    }))
+}
+
+/// Admin routes are only served when authentication can enforce the `admin`
+/// role; otherwise any anonymous client could use them
+fn require_authentication(state: &AppState) -> Result<(), (StatusCode, String)> {
+    if state.authenticator.is_none() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "admin routes require authentication to be enabled".to_string(),
+        ));
+    }
+    Ok(())
+}
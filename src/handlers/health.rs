@@ -1,8 +1,11 @@
-use axum::{http::StatusCode, response::Json};
+use crate::app::AppState;
+use crate::readiness::{CheckResult, CheckStatus};
+use axum::{extract::State, http::StatusCode, response::Json};
 use chrono::{DateTime, Utc};
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct HealthResponse {
     pub status: String,
     pub service: String,
@@ -10,13 +13,32 @@ pub struct HealthResponse {
     pub timestamp: DateTime<Utc>,
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct LivenessResponse {
+    pub status: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    pub status: String,
+    pub checks: Vec<CheckResult>,
+}
+
 /// Health check endpoint
 ///
 /// Returns service status, version, and current timestamp
-pub async fn health_check() -> Result<Json<HealthResponse>, StatusCode> {
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "Service is healthy", body = HealthResponse))
+)]
+pub async fn health_check(
+    State(state): State<AppState>,
+) -> Result<Json<HealthResponse>, StatusCode> {
     let health_response = HealthResponse {
         status: "healthy".to_string(),
-        service: "ai-model-service-abhinaw".to_string(), // Updated for production deployment test
+        service: state.config.server.service_name.clone(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         timestamp: Utc::now(),
     };
@@ -24,3 +46,55 @@ pub async fn health_check() -> Result<Json<HealthResponse>, StatusCode> {
     tracing::info!("Health check requested");
     Ok(Json(health_response))
 }
+
+/// Liveness probe endpoint
+///
+/// Succeeds whenever the process can serve HTTP; it deliberately checks
+/// nothing else so a slow dependency never gets the pod restarted
+#[utoipa::path(
+    get,
+    path = "/livez",
+    tag = "health",
+    responses((status = 200, description = "Process is alive", body = LivenessResponse))
+)]
+pub async fn liveness() -> Json<LivenessResponse> {
+    Json(LivenessResponse {
+        status: "alive".to_string(),
+    })
+}
+
+/// Readiness probe endpoint
+///
+/// Returns 503 until the model is warmed, while draining for shutdown, or
+/// when a dependency check fails, with per-check detail in the body
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    responses(
+        (status = 200, description = "Service is ready for traffic", body = ReadinessResponse),
+        (status = 503, description = "Service is not ready", body = ReadinessResponse)
+    )
+)]
+pub async fn readiness(State(state): State<AppState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let checks = state.readiness.check().await;
+    let ready = checks.iter().all(|c| c.status == CheckStatus::Pass);
+
+    if !ready {
+        tracing::warn!(?checks, "Readiness check failed");
+    }
+
+    let (status_code, status) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+
+    (
+        status_code,
+        Json(ReadinessResponse {
+            status: status.to_string(),
+            checks,
+        }),
+    )
+}
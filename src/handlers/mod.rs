@@ -1,2 +1,5 @@
+pub mod admin;
 pub mod health;
+pub mod models;
 pub mod predict;
+pub mod slo;
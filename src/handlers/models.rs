@@ -0,0 +1,98 @@
+use crate::app::AppState;
+use crate::metrics::drift::DriftReport;
+use crate::models::ml_model::{get_model, ModelMetadata};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct ModelListResponse {
+    pub models: Vec<ModelMetadata>,
+}
+
+/// Model listing endpoint
+///
+/// Returns metadata and input schema for every loaded model
+#[utoipa::path(
+    get,
+    path = "/models",
+    tag = "models",
+    responses(
+        (status = 200, description = "Loaded models", body = ModelListResponse),
+        (status = 503, description = "Model is still loading")
+    )
+)]
+pub async fn list_models() -> Result<Json<ModelListResponse>, StatusCode> {
+    let metadata = get_model()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?
+        .metadata();
+
+    Ok(Json(ModelListResponse {
+        models: vec![metadata],
+    }))
+}
+
+/// Model metadata endpoint
+///
+/// Returns metadata for a single model by name, or 404 if it is not loaded
+#[utoipa::path(
+    get,
+    path = "/models/{name}",
+    tag = "models",
+    params(("name" = String, Path, description = "Model name", example = "linear-regression")),
+    responses(
+        (status = 200, description = "Model metadata", body = ModelMetadata),
+        (status = 404, description = "Model not loaded"),
+        (status = 503, description = "Model is still loading")
+    )
+)]
+pub async fn get_model_metadata(
+    Path(name): Path<String>,
+) -> Result<Json<ModelMetadata>, StatusCode> {
+    let metadata = get_model()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?
+        .metadata();
+
+    if metadata.name != name {
+        tracing::debug!(model = %name, "Unknown model requested");
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    Ok(Json(metadata))
+}
+
+/// Model input drift endpoint
+///
+/// Compares the inputs of recent predictions with the training distribution
+/// recorded in the model artifact
+#[utoipa::path(
+    get,
+    path = "/models/{name}/drift",
+    tag = "models",
+    params(("name" = String, Path, description = "Model name", example = "linear-regression")),
+    responses(
+        (status = 200, description = "Per-feature input statistics and drift scores", body = DriftReport),
+        (status = 404, description = "Model not loaded"),
+        (status = 503, description = "Drift detection is disabled or the model is still loading")
+    )
+)]
+pub async fn get_model_drift(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<DriftReport>, StatusCode> {
+    let model = get_model().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    if model.metadata().name != name {
+        tracing::debug!(model = %name, "Unknown model requested");
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let drift = state
+        .drift
+        .as_ref()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+    Ok(Json(drift.report(model)))
+}
@@ -1,41 +1,123 @@
-use crate::metrics::prometheus::record_ml_prediction;
+use crate::app::AppState;
+use crate::audit::AuditRecord;
+use crate::auth::Principal;
+use crate::metrics::openmetrics::ExemplarLabels;
 use crate::models::ml_model::{get_model, PredictionRequest, PredictionResponse};
-use axum::{extract::Json, http::StatusCode};
+use crate::request_id::RequestId;
+use crate::telemetry;
+use crate::tls::ClientIdentity;
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{Extension, Json, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use std::time::Instant;
 
 /// Prediction endpoint
 ///
 /// Accepts a JSON payload with features and returns ML model prediction
+#[utoipa::path(
+    post,
+    path = "/predict",
+    tag = "inference",
+    request_body = PredictionRequest,
+    responses(
+        (status = 200, description = "Prediction computed", body = PredictionResponse),
+        (status = 400, description = "Invalid feature values or feature count, or inputs outside the training data when `ood.reject` is set"),
+        (status = 401, description = "Missing or invalid credentials (when authentication is enabled)"),
+        (status = 413, description = "Request body exceeds the configured limit"),
+        (status = 422, description = "Request body does not match the schema"),
+        (status = 429, description = "Rate limit exceeded; see the Retry-After and RateLimit-* headers"),
+        (status = 503, description = "Service overloaded and shedding load (retry after the Retry-After delay), or the model is still loading"),
+        (status = 504, description = "Request exceeded the route timeout")
+    )
+)]
 pub async fn predict(
+    State(state): State<AppState>,
+    client: Option<Extension<ClientIdentity>>,
+    principal: Option<Extension<Principal>>,
+    request_id: Option<Extension<RequestId>>,
     Json(request): Json<PredictionRequest>,
 ) -> Result<Json<PredictionResponse>, StatusCode> {
+    let started = Instant::now();
+    let subject = principal.map(|Extension(p)| p.subject);
     tracing::info!(
         feature_count = request.features.len(),
+        client = client.as_ref().map(|Extension(c)| c.display_name()),
+        subject = subject.as_deref(),
         "Prediction request received"
     );
 
-    // Get the global model instance dcxdcd
-    let model = get_model();
+    // Get the global model instance; absent while the artifact is loading
+    let Some(model) = get_model() else {
+        tracing::warn!("Prediction requested before the model finished loading");
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
 
     // Perform prediction
-    match model.predict(&request.features) {
-        Ok(prediction_response) => {
+    let result = model.predict(&request.features);
+    let ood_score = result.as_ref().ok().and_then(|response| response.ood_score);
+    let result = result.and_then(|response| screen_ood(&state, response));
+    // Inputs refused by the screen would skew the drift windows
+    if let (Ok(_), Some(drift)) = (&result, &state.drift) {
+        drift.observe(model, &request.features);
+    }
+    let mut audit_record = AuditRecord {
+        request_id: request_id
+            .map(|Extension(RequestId(id))| id)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        timestamp: Utc::now(),
+        model_version: model.version().to_string(),
+        subject,
+        features: request.features,
+        prediction: None,
+        confidence: None,
+        ood_score,
+        error: None,
+        latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+    };
+
+    let exemplar = ExemplarLabels {
+        trace_id: telemetry::current_trace_id(),
+        request_id: Some(audit_record.request_id.clone()),
+    };
+
+    let response = match result {
+        Ok(mut prediction_response) => {
+            prediction_response.prediction_id = Some(audit_record.request_id.clone());
+            audit_record.prediction = Some(prediction_response.prediction);
+            audit_record.confidence = Some(prediction_response.confidence);
+
             // Record successful prediction metrics
-            record_ml_prediction(
+            state.metrics.record_ml_prediction(
                 &prediction_response.model_version,
+                prediction_response.prediction,
                 prediction_response.confidence,
                 true,
+                &exemplar,
             );
+            if let Some(outputs) = &state.outputs {
+                outputs.observe(
+                    &prediction_response.model_version,
+                    prediction_response.prediction,
+                );
+            }
 
             tracing::info!(
-                prediction = %prediction_response.prediction,
-                confidence = %prediction_response.confidence,
+                prediction = prediction_response.prediction,
+                confidence = prediction_response.confidence,
+                model_version = %prediction_response.model_version,
                 "Prediction completed successfully"
             );
             Ok(Json(prediction_response))
         }
         Err(e) => {
             // Record failed prediction metrics
-            record_ml_prediction("v1.0.0", 0.0, false);
+            state
+                .metrics
+                .record_ml_prediction(model.version(), 0.0, 0.0, false, &exemplar);
+            audit_record.error = Some(e.to_string());
 
             tracing::error!(
                 error = %e,
@@ -44,5 +126,41 @@ pub async fn predict(
             // Return bad request for invalid input
             Err(StatusCode::BAD_REQUEST)
         }
+    };
+
+    if let Some(auditor) = &state.auditor {
+        auditor.record(audit_record);
+    }
+
+    response
+}
+
+/// Count, and with `ood.reject` refuse, predictions whose inputs are far
+/// outside the training data
+fn screen_ood(state: &AppState, response: PredictionResponse) -> Result<PredictionResponse> {
+    let ood = &state.config.ood;
+    let Some(score) = response
+        .ood_score
+        .filter(|score| ood.enabled && *score > ood.z_threshold)
+    else {
+        return Ok(response);
+    };
+
+    state
+        .metrics
+        .record_ood(&response.model_version, ood.reject);
+    tracing::warn!(
+        ood_score = score,
+        z_threshold = ood.z_threshold,
+        rejected = ood.reject,
+        "Prediction input outside the training data"
+    );
+    if ood.reject {
+        return Err(anyhow!(
+            "Features outside the training data: ood_score {:.2} exceeds {}",
+            score,
+            ood.z_threshold
+        ));
     }
+    Ok(response)
 }
@@ -0,0 +1,21 @@
+use crate::app::AppState;
+use crate::metrics::slo::SloReport;
+use axum::{extract::State, http::StatusCode, response::Json};
+
+/// SLO endpoint
+///
+/// Returns error budget burn rates of the `/predict` availability and latency
+/// objectives over several windows, and which burn rate alerts are firing
+#[utoipa::path(
+    get,
+    path = "/slo",
+    tag = "observability",
+    responses(
+        (status = 200, description = "Burn rates and alert states per objective", body = SloReport),
+        (status = 503, description = "SLO tracking is disabled")
+    )
+)]
+pub async fn get_slo(State(state): State<AppState>) -> Result<Json<SloReport>, StatusCode> {
+    let slo = state.slo.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(Json(slo.report()))
+}
@@ -1,3 +1,18 @@
+pub mod app;
+pub mod audit;
+pub mod auth;
+pub mod bench;
+pub mod config;
 pub mod handlers;
+pub mod limits;
+pub mod logging;
 pub mod metrics;
 pub mod models;
+pub mod openapi;
+pub mod rate_limit;
+pub mod readiness;
+pub mod replay;
+pub mod request_id;
+pub mod shutdown;
+pub mod telemetry;
+pub mod tls;
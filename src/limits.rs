@@ -0,0 +1,381 @@
+use crate::app::path_matches_prefix;
+use crate::config::Config;
+use crate::metrics::prometheus::AppMetrics;
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Weight of the newest sample in the queue delay moving average
+const QUEUE_DELAY_EWMA_ALPHA: f64 = 0.2;
+
+/// Why a request was rejected before or while being handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    /// Handling exceeded the route timeout
+    Timeout,
+    /// Request body larger than `limits.max_body_bytes`
+    BodyTooLarge,
+    /// Queue for a concurrency slot was full
+    QueueFull,
+    /// Waited longer than `limits.queue_timeout_ms` for a slot
+    QueueTimeout,
+    /// Shed because recent queue delay exceeded `limits.shed_queue_delay_ms`
+    LoadShed,
+}
+
+impl RejectReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RejectReason::Timeout => "timeout",
+            RejectReason::BodyTooLarge => "body_too_large",
+            RejectReason::QueueFull => "queue_full",
+            RejectReason::QueueTimeout => "queue_timeout",
+            RejectReason::LoadShed => "load_shed",
+        }
+    }
+}
+
+impl IntoResponse for RejectReason {
+    fn into_response(self) -> Response {
+        match self {
+            RejectReason::Timeout => {
+                (StatusCode::GATEWAY_TIMEOUT, "request timed out").into_response()
+            }
+            RejectReason::BodyTooLarge => {
+                (StatusCode::PAYLOAD_TOO_LARGE, "request body too large").into_response()
+            }
+            RejectReason::QueueFull | RejectReason::QueueTimeout | RejectReason::LoadShed => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                [(header::RETRY_AFTER, "1")],
+                "service overloaded",
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// Decrements the queue length when a waiting request leaves the queue,
+/// including when it is cancelled
+struct QueueSlot<'a>(&'a AtomicUsize);
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Per-route timeouts, body size limit, concurrency limit and load shedding
+pub struct RequestLimits {
+    max_body_bytes: usize,
+    default_timeout: Duration,
+    route_timeouts: Vec<(String, Duration)>,
+    exempt_prefixes: Vec<String>,
+    permits: Arc<Semaphore>,
+    max_queue_length: usize,
+    queue_timeout: Duration,
+    shed_queue_delay: Duration,
+    queued: AtomicUsize,
+    queue_delay_ewma: Mutex<Duration>,
+    metrics: Arc<AppMetrics>,
+}
+
+impl RequestLimits {
+    pub fn from_config(config: &Config, metrics: Arc<AppMetrics>) -> Self {
+        let mut route_timeouts: Vec<_> = config
+            .timeouts
+            .routes
+            .iter()
+            .map(|route| {
+                (
+                    route.prefix.clone(),
+                    Duration::from_millis(route.timeout_ms),
+                )
+            })
+            .collect();
+        // Longest prefix first so the most specific timeout wins
+        route_timeouts.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        Self {
+            max_body_bytes: config.limits.max_body_bytes,
+            default_timeout: config.request_timeout(),
+            route_timeouts,
+            exempt_prefixes: config.limits.exempt_prefixes.clone(),
+            permits: Arc::new(Semaphore::new(config.limits.max_concurrent_requests)),
+            max_queue_length: config.limits.max_queue_length,
+            queue_timeout: Duration::from_millis(config.limits.queue_timeout_ms),
+            shed_queue_delay: Duration::from_millis(config.limits.shed_queue_delay_ms),
+            queued: AtomicUsize::new(0),
+            queue_delay_ewma: Mutex::new(Duration::ZERO),
+            metrics,
+        }
+    }
+
+    /// Count the rejection and build its response
+    fn reject(&self, reason: RejectReason) -> Response {
+        self.metrics.record_rejected_request(reason.as_str());
+        reason.into_response()
+    }
+
+    /// Timeout for a request path
+    pub fn timeout_for(&self, path: &str) -> Duration {
+        self.route_timeouts
+            .iter()
+            .find(|(prefix, _)| path_matches_prefix(path, prefix))
+            .map(|(_, timeout)| *timeout)
+            .unwrap_or(self.default_timeout)
+    }
+
+    fn is_exempt(&self, path: &str) -> bool {
+        self.exempt_prefixes
+            .iter()
+            .any(|prefix| path_matches_prefix(path, prefix))
+    }
+
+    /// Recent average time requests spent waiting for a slot
+    pub fn queue_delay(&self) -> Duration {
+        *self
+            .queue_delay_ewma
+            .lock()
+            .expect("queue delay lock poisoned")
+    }
+
+    fn observe_queue_delay(&self, delay: Duration) {
+        let mut ewma = self
+            .queue_delay_ewma
+            .lock()
+            .expect("queue delay lock poisoned");
+        *ewma = ewma.mul_f64(1.0 - QUEUE_DELAY_EWMA_ALPHA) + delay.mul_f64(QUEUE_DELAY_EWMA_ALPHA);
+    }
+
+    /// Wait for a concurrency slot, or decide to reject the request
+    ///
+    /// Requests only queue when every slot is busy. While the recent average
+    /// queue delay is above the shed threshold, such requests are rejected
+    /// immediately instead of queueing; immediate admissions pull the average
+    /// back down as load subsides.
+    async fn admit(&self) -> Result<OwnedSemaphorePermit, RejectReason> {
+        if let Ok(permit) = self.permits.clone().try_acquire_owned() {
+            self.observe_queue_delay(Duration::ZERO);
+            return Ok(permit);
+        }
+
+        if self.queue_delay() > self.shed_queue_delay {
+            return Err(RejectReason::LoadShed);
+        }
+
+        let position = self.queued.fetch_add(1, Ordering::SeqCst) + 1;
+        let _slot = QueueSlot(&self.queued);
+        if position > self.max_queue_length {
+            return Err(RejectReason::QueueFull);
+        }
+
+        let started = Instant::now();
+        match tokio::time::timeout(self.queue_timeout, self.permits.clone().acquire_owned()).await {
+            Ok(Ok(permit)) => {
+                self.observe_queue_delay(started.elapsed());
+                Ok(permit)
+            }
+            // The semaphore is never closed; treat it like a timeout regardless
+            Ok(Err(_)) | Err(_) => {
+                self.observe_queue_delay(self.queue_timeout);
+                Err(RejectReason::QueueTimeout)
+            }
+        }
+    }
+}
+
+/// Enforce body size, concurrency, load shedding and per-route timeouts
+///
+/// Bodies without a `Content-Length` are still capped by axum's
+/// `DefaultBodyLimit`; those rejections are counted here too.
+pub async fn limits_middleware(
+    State(limits): State<Arc<RequestLimits>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+
+    let content_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if content_length.is_some_and(|length| length > limits.max_body_bytes) {
+        return limits.reject(RejectReason::BodyTooLarge);
+    }
+
+    let _permit = if limits.is_exempt(&path) {
+        None
+    } else {
+        match limits.admit().await {
+            Ok(permit) => Some(permit),
+            Err(reason) => {
+                tracing::warn!(
+                    path = %path,
+                    reason = reason.as_str(),
+                    queue_delay_ms = limits.queue_delay().as_millis() as u64,
+                    "Request rejected under load"
+                );
+                return limits.reject(reason);
+            }
+        }
+    };
+
+    let timeout = limits.timeout_for(&path);
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => {
+            if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+                limits
+                    .metrics
+                    .record_rejected_request(RejectReason::BodyTooLarge.as_str());
+            }
+            response
+        }
+        Err(_) => {
+            tracing::warn!(path = %path, timeout_ms = timeout.as_millis() as u64, "Request timed out");
+            limits.reject(RejectReason::Timeout)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RouteTimeoutConfig;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn limits(configure: impl FnOnce(&mut Config)) -> Arc<RequestLimits> {
+        let mut config = Config::default();
+        configure(&mut config);
+        Arc::new(RequestLimits::from_config(
+            &config,
+            Arc::new(AppMetrics::new().unwrap()),
+        ))
+    }
+
+    fn app(limits: Arc<RequestLimits>) -> Router {
+        Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    "done"
+                }),
+            )
+            .route("/fast", get(|| async { "done" }))
+            .layer(middleware::from_fn_with_state(limits, limits_middleware))
+    }
+
+    fn rejected(limits: &RequestLimits, reason: RejectReason) -> f64 {
+        limits
+            .metrics
+            .http_requests_rejected_total
+            .with_label_values(&[reason.as_str()])
+            .get()
+    }
+
+    fn get_request(uri: &str) -> Request {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_route_timeouts() {
+        let limits = limits(|config| {
+            config.timeouts.routes = vec![RouteTimeoutConfig {
+                prefix: "/predict".to_string(),
+                timeout_ms: 500,
+            }];
+        });
+
+        assert_eq!(limits.timeout_for("/predict"), Duration::from_millis(500));
+        assert_eq!(limits.timeout_for("/models"), Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn test_route_timeout_rejects_slow_request() {
+        let limits = limits(|config| {
+            config.timeouts.routes = vec![RouteTimeoutConfig {
+                prefix: "/slow".to_string(),
+                timeout_ms: 20,
+            }];
+        });
+
+        let response = app(limits.clone())
+            .oneshot(get_request("/slow"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(rejected(&limits, RejectReason::Timeout), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_content_length_over_limit() {
+        let limits = limits(|config| config.limits.max_body_bytes = 8);
+        let request = Request::builder()
+            .uri("/fast")
+            .header(header::CONTENT_LENGTH, "100")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app(limits.clone()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(rejected(&limits, RejectReason::BodyTooLarge), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_queue_full_and_queue_timeout() {
+        let limits = limits(|config| {
+            config.limits.max_concurrent_requests = 1;
+            config.limits.max_queue_length = 1;
+            config.limits.queue_timeout_ms = 50;
+            config.limits.shed_queue_delay_ms = 10_000;
+        });
+        let app = app(limits.clone());
+
+        // Occupy the only slot, then the only queue position
+        let busy = tokio::spawn(app.clone().oneshot(get_request("/slow")));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let queued = tokio::spawn(app.clone().oneshot(get_request("/fast")));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let response = app.clone().oneshot(get_request("/fast")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+
+        let response = queued.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(busy.await.unwrap().unwrap().status(), StatusCode::OK);
+        assert_eq!(rejected(&limits, RejectReason::QueueFull), 1.0);
+        assert_eq!(rejected(&limits, RejectReason::QueueTimeout), 1.0);
+
+        // Exempt routes bypass the limit
+        assert!(limits.is_exempt("/livez"));
+    }
+
+    #[tokio::test]
+    async fn test_sheds_while_queue_delay_high() {
+        let limits = limits(|config| {
+            config.limits.max_concurrent_requests = 1;
+            config.limits.shed_queue_delay_ms = 5;
+        });
+        for _ in 0..10 {
+            limits.observe_queue_delay(Duration::from_millis(100));
+        }
+        let app = app(limits.clone());
+
+        let busy = tokio::spawn(app.clone().oneshot(get_request("/slow")));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let response = app.oneshot(get_request("/fast")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(busy.await.unwrap().unwrap().status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::config::{LogFormat, LoggingConfig};
+use anyhow::{anyhow, Context, Result};
+use axum::http::Response;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{Span, Subscriber};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+/// Log filter layer that can be swapped at runtime
+pub type FilterLayer = reload::Layer<EnvFilter, Registry>;
+
+/// Changes the active log filter without a restart
+#[derive(Clone)]
+pub struct LogLevelHandle {
+    handle: reload::Handle<EnvFilter, Registry>,
+    current: Arc<Mutex<String>>,
+}
+
+impl LogLevelHandle {
+    /// Active filter directives
+    pub fn current(&self) -> String {
+        self.current
+            .lock()
+            .expect("log filter lock poisoned")
+            .clone()
+    }
+
+    /// Replace the active filter, e.g. `"info,ai_model_service=debug"`
+    pub fn set(&self, filter: &str) -> Result<()> {
+        let env_filter = EnvFilter::try_new(filter)
+            .with_context(|| format!("Invalid log filter {:?}", filter))?;
+        self.handle
+            .reload(env_filter)
+            .map_err(|e| anyhow!("Failed to apply log filter: {}", e))?;
+
+        *self.current.lock().expect("log filter lock poisoned") = filter.to_string();
+        Ok(())
+    }
+}
+
+/// Filter layer for the bottom of the subscriber stack, plus its reload handle
+pub fn filter_layer(filter: &str) -> Result<(FilterLayer, LogLevelHandle)> {
+    let env_filter =
+        EnvFilter::try_new(filter).with_context(|| format!("Invalid log filter {:?}", filter))?;
+    let (layer, handle) = reload::Layer::new(env_filter);
+
+    Ok((
+        layer,
+        LogLevelHandle {
+            handle,
+            current: Arc::new(Mutex::new(filter.to_string())),
+        },
+    ))
+}
+
+/// Formatting layer for the configured format and sink
+///
+/// JSON lines flatten event fields to the top level and include the current
+/// span, whose `request_id`, `method` and `uri` fields identify the request.
+pub fn fmt_layer<S>(config: &LoggingConfig) -> Result<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let (writer, ansi) = match &config.file_path {
+        Some(path) => {
+            let file = RotatingFile::open(path, config.max_file_bytes, Some(config.max_files))?;
+            (BoxMakeWriter::new(Arc::new(file)), false)
+        }
+        None => (BoxMakeWriter::new(io::stdout), true),
+    };
+
+    Ok(match config.format {
+        LogFormat::Text => fmt::layer().with_ansi(ansi).with_writer(writer).boxed(),
+        LogFormat::Json => fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .with_writer(writer)
+            .boxed(),
+    })
+}
+
+/// `TraceLayer` response hook: one line per request with its status and latency
+pub fn log_response<B>(response: &Response<B>, latency: Duration, _span: &Span) {
+    tracing::info!(
+        status = response.status().as_u16(),
+        latency_ms = latency.as_secs_f64() * 1000.0,
+        "Request completed"
+    );
+}
+
+/// Log file rotated by size: `<file>` is active, `<file>.1` the newest
+/// rotated file, up to `<file>.<max_files>`; without `max_files` every
+/// rotated file is kept
+pub struct RotatingFile {
+    state: Mutex<RotatingState>,
+}
+
+struct RotatingState {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    max_files: Option<usize>,
+}
+
+impl RotatingFile {
+    pub fn open(path: &Path, max_bytes: u64, max_files: Option<usize>) -> Result<Self> {
+        // Rotating into zero files would truncate the active one
+        anyhow::ensure!(max_files != Some(0), "max_files must be greater than 0");
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create log directory {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            state: Mutex::new(RotatingState {
+                path: path.to_path_buf(),
+                file,
+                size,
+                max_bytes,
+                max_files,
+            }),
+        })
+    }
+
+    /// Flush written data to disk
+    pub fn sync(&self) -> io::Result<()> {
+        self.state
+            .lock()
+            .expect("log file lock poisoned")
+            .file
+            .sync_data()
+    }
+}
+
+impl RotatingState {
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        // Shift <file>.N-1 -> <file>.N, overwriting the oldest when bounded
+        let last = match self.max_files {
+            Some(max_files) => max_files,
+            None => (1..)
+                .find(|&index| !self.rotated_path(index).exists())
+                .expect("unbounded range"),
+        };
+        for index in (1..last).rev() {
+            match std::fs::rename(self.rotated_path(index), self.rotated_path(index + 1)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        std::fs::rename(&self.path, self.rotated_path(1))?;
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for &RotatingFile {
+    // The formatter writes each event in a single call, and each call is
+    // written whole to one file, so events are never split across files
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.lock().expect("log file lock poisoned");
+        if state.size > 0 && state.size + buf.len() as u64 > state.max_bytes {
+            state.rotate()?;
+        }
+
+        state.file.write_all(buf)?;
+        state.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.state
+            .lock()
+            .expect("log file lock poisoned")
+            .file
+            .flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotating_file_keeps_max_files() {
+        let dir = std::env::temp_dir().join(format!("logs-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("service.log");
+        let file = RotatingFile::open(&path, 10, Some(2)).unwrap();
+
+        for line in ["aaaaaaaa\n", "bbbbbbbb\n", "cccccccc\n", "dddddddd\n"] {
+            (&file).write_all(line.as_bytes()).unwrap();
+        }
+
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("service.log"), "dddddddd\n");
+        assert_eq!(read("service.log.1"), "cccccccc\n");
+        assert_eq!(read("service.log.2"), "bbbbbbbb\n");
+        assert!(!dir.join("service.log.3").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotating_file_unbounded_keeps_every_file() {
+        let dir = std::env::temp_dir().join(format!("logs-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("audit.ndjson");
+        let file = RotatingFile::open(&path, 10, None).unwrap();
+
+        for line in ["aaaaaaaa\n", "bbbbbbbb\n", "cccccccc\n", "dddddddd\n"] {
+            (&file).write_all(line.as_bytes()).unwrap();
+        }
+
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("audit.ndjson"), "dddddddd\n");
+        assert_eq!(read("audit.ndjson.1"), "cccccccc\n");
+        assert_eq!(read("audit.ndjson.3"), "aaaaaaaa\n");
+        assert!(RotatingFile::open(&path, 10, Some(0)).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_log_level_reload() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let (layer, handle) = filter_layer("info").unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            assert!(!tracing::enabled!(tracing::Level::DEBUG));
+            handle.set("debug").unwrap();
+            assert!(tracing::enabled!(tracing::Level::DEBUG));
+        });
+
+        assert_eq!(handle.current(), "debug");
+        assert!(handle.set("not a [valid filter").is_err());
+        assert_eq!(handle.current(), "debug");
+    }
+}
@@ -1,50 +1,168 @@
-use axum::{
-    middleware,
-    routing::{get, post},
-    Router,
-};
-use tower::ServiceBuilder;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use ai_model_service::app::{self, AppState};
+use ai_model_service::config::{Cli, Command, Config};
+use ai_model_service::metrics::connections::{CountingAcceptor, CountingListener};
+use ai_model_service::models::ml_model;
+use ai_model_service::{audit, bench, logging, replay, shutdown, telemetry, tls};
+use axum::serve::ListenerExt;
+use axum_server::tls_rustls::RustlsConfig;
+use clap::Parser;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::Notify;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-mod handlers;
-mod metrics;
-mod models;
-
-use handlers::{health, predict};
-use metrics::prometheus::setup_metrics_recorder;
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize structured logging
+    // Load and validate configuration before anything else
+    let cli = Cli::parse();
+    match cli.command.clone() {
+        Some(Command::Replay(args)) => return replay::run(args).await,
+        // The in-process benchmark honours the same config file and flags
+        Some(Command::Bench(args)) => return bench::run(args, Config::load(&cli)?).await,
+        None => {}
+    }
+    let config = Config::load(&cli)?;
+
+    // Export spans over OTLP when enabled
+    let tracer_provider = if config.telemetry.enabled {
+        Some(telemetry::init_tracer_provider(
+            &config.telemetry,
+            &config.server.service_name,
+        )?)
+    } else {
+        None
+    };
+
+    // Initialize structured logging; the filter can be changed at runtime
+    let (filter_layer, log_level) = logging::filter_layer(&config.server.log_filter)?;
     tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "ai_model_service=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
+        .with(filter_layer)
+        .with(logging::fmt_layer(&config.logging)?)
+        .with(tracer_provider.as_ref().map(telemetry::layer))
         .init();
 
-    // Setup Prometheus metrics
-    setup_metrics_recorder()?;
+    let bind_address = config.server.bind_address;
+    let model_path = config.model.path.clone();
+    let drain_delay = config.drain_delay();
+    let grace_period = config.grace_period();
+    let tls_config = config.tls.clone();
+    let mut state = AppState::new(config)?;
+    state.log_level = Some(log_level);
+
+    // Record predictions to the audit log in the background
+    let audit_writer = if state.config.audit.enabled {
+        let (auditor, writer) = audit::spawn(&state.config.audit, state.metrics.clone())?;
+        tracing::info!(path = %state.config.audit.path.display(), "Prediction audit log enabled");
+        state.auditor = Some(auditor);
+        Some(writer)
+    } else {
+        None
+    };
+
+    // Load and warm the model in the background; readiness fails until done.
+    // Without a model the service can never become ready, so exit and let
+    // the orchestrator restart it rather than serve 503s indefinitely.
+    let readiness = state.readiness.clone();
+    tokio::task::spawn_blocking(move || {
+        match ml_model::load_model(model_path.as_deref()).and_then(|_| ml_model::warm_up()) {
+            Ok(()) => {
+                readiness.set_model_ready();
+                tracing::info!("Model loaded and warmed");
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Model failed to load, exiting");
+                std::process::exit(1);
+            }
+        }
+    });
 
     // Build application router with all routes
-    let app = Router::new()
-        .route("/health", get(health::health_check))
-        .route("/predict", post(predict::predict))
-        .route("/metrics", get(metrics::prometheus::metrics_handler))
-        .layer(
-            ServiceBuilder::new()
-                .layer(TraceLayer::new_for_http())
-                .layer(CorsLayer::permissive()),
-        )
-        .layer(middleware::from_fn(metrics::middleware::metrics_middleware));
+    let readiness = state.readiness.clone();
+    let metrics = state.metrics.clone();
+    let app = app::create_router(state);
+
+    // On SIGTERM/SIGINT: fail readiness, drain, then stop accepting and let
+    // in-flight requests finish within the grace period
+    let draining = Arc::new(Notify::new());
+    let shutdown = shutdown::drain(
+        shutdown::termination_signal(),
+        readiness,
+        drain_delay,
+        draining.clone(),
+    );
 
     // Start the server
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
-    tracing::info!("🚀 AI Model Service starting on http://0.0.0.0:3000");
+    if tls_config.enabled {
+        let rustls_config = RustlsConfig::from_config(tls::load_server_config(&tls_config)?);
+        tls::spawn_reloader(tls_config.clone(), rustls_config.clone());
+
+        let handle = axum_server::Handle::new();
+        tokio::spawn({
+            let handle = handle.clone();
+            async move {
+                shutdown.await;
+                handle.graceful_shutdown(Some(grace_period));
+            }
+        });
+
+        tracing::info!(
+            mtls = tls_config.client_ca_path.is_some(),
+            "🚀 AI Model Service starting on https://{}",
+            bind_address
+        );
+        axum_server::bind(bind_address)
+            .acceptor(CountingAcceptor::new(
+                tls::ClientIdentityAcceptor::new(rustls_config),
+                metrics,
+            ))
+            .handle(handle)
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+            .await?;
+    } else {
+        // axum only provides `ConnectInfo<SocketAddr>` for `TcpListener` and
+        // `TapIo` listeners, hence the no-op tap
+        let listener =
+            CountingListener::new(tokio::net::TcpListener::bind(bind_address).await?, metrics)
+                .tap_io(|_| {});
+        tracing::info!("🚀 AI Model Service starting on http://{}", bind_address);
+
+        let server = axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(shutdown);
+        let grace_deadline = async {
+            draining.notified().await;
+            tokio::time::sleep(grace_period).await;
+        };
+
+        tokio::select! {
+            result = server => result?,
+            _ = grace_deadline => tracing::warn!(
+                grace_period_secs = grace_period.as_secs_f64(),
+                "Grace period elapsed, abandoning remaining in-flight requests"
+            ),
+        }
+    }
+
+    // Audit records still buffered are written before exiting
+    if let Some(writer) = audit_writer {
+        match writer.shutdown().await {
+            Ok(()) => tracing::info!("Flushed prediction audit log"),
+            Err(e) => tracing::error!(error = %e, "Failed to flush prediction audit log"),
+        }
+    }
 
-    axum::serve(listener, app).await?;
+    // Metrics are pull-based and stay scrapeable throughout the drain delay,
+    // so there is nothing buffered to flush for them here. Spans are batched
+    // and must be flushed to the collector.
+    if let Some(provider) = tracer_provider {
+        match tokio::task::spawn_blocking(move || provider.shutdown()).await? {
+            Ok(()) => tracing::info!("Flushed pending trace spans"),
+            Err(e) => tracing::warn!(error = %e, "Failed to flush trace spans"),
+        }
+    }
+    tracing::info!("Shutdown complete");
 
     Ok(())
 }
@@ -0,0 +1,187 @@
+use crate::metrics::prometheus::AppMetrics;
+use axum::serve::Listener;
+use axum_server::accept::Accept;
+use prometheus::Gauge;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Counts one open connection in `active_connections_total` for as long as
+/// it lives
+///
+/// Decrementing on drop keeps the gauge right however the connection ends:
+/// closed by either side, aborted on shutdown, or torn down by a panic.
+pub struct ConnectionGuard(Gauge);
+
+impl ConnectionGuard {
+    pub fn new(metrics: &AppMetrics) -> Self {
+        metrics.active_connections.inc();
+        Self(metrics.active_connections.clone())
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+/// Connection IO that is counted until it is dropped
+pub struct CountedIo<T> {
+    inner: T,
+    _guard: ConnectionGuard,
+}
+
+impl<T> CountedIo<T> {
+    pub fn new(inner: T, metrics: &AppMetrics) -> Self {
+        Self {
+            inner,
+            _guard: ConnectionGuard::new(metrics),
+        }
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for CountedIo<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for CountedIo<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// `axum::serve` listener counting every accepted connection
+pub struct CountingListener<L> {
+    listener: L,
+    metrics: Arc<AppMetrics>,
+}
+
+impl<L> CountingListener<L> {
+    pub fn new(listener: L, metrics: Arc<AppMetrics>) -> Self {
+        Self { listener, metrics }
+    }
+}
+
+impl<L> Listener for CountingListener<L>
+where
+    L: Listener,
+{
+    type Io = CountedIo<L::Io>;
+    type Addr = L::Addr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        let (io, addr) = self.listener.accept().await;
+        (CountedIo::new(io, &self.metrics), addr)
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.listener.local_addr()
+    }
+}
+
+/// `axum_server` acceptor counting every accepted connection, including
+/// ones still in the TLS handshake
+#[derive(Clone)]
+pub struct CountingAcceptor<A> {
+    acceptor: A,
+    metrics: Arc<AppMetrics>,
+}
+
+impl<A> CountingAcceptor<A> {
+    pub fn new(acceptor: A, metrics: Arc<AppMetrics>) -> Self {
+        Self { acceptor, metrics }
+    }
+}
+
+impl<A, I, S> Accept<I, S> for CountingAcceptor<A>
+where
+    A: Accept<CountedIo<I>, S>,
+{
+    type Stream = A::Stream;
+    type Service = A::Service;
+    type Future = A::Future;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        self.acceptor
+            .accept(CountedIo::new(stream, &self.metrics), service)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_connections_counted_until_closed() {
+        let tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = tcp.local_addr().unwrap();
+        let metrics = Arc::new(AppMetrics::new().unwrap());
+        let mut listener = CountingListener::new(tcp, metrics.clone());
+        let open = || metrics.active_connections.get();
+
+        let mut client = tokio::net::TcpStream::connect(address).await.unwrap();
+        let (mut server, _) = listener.accept().await;
+        assert_eq!(open(), 1.0);
+
+        // IO passes straight through
+        client.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        server.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+
+        drop(server);
+        assert_eq!(open(), 0.0);
+
+        // Also released when the task owning the connection panics
+        let (server, _) = {
+            let _client = tokio::net::TcpStream::connect(address).await.unwrap();
+            listener.accept().await
+        };
+        assert_eq!(open(), 1.0);
+        let result = tokio::spawn(async move {
+            let _server = server;
+            panic!("connection task failed");
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(open(), 0.0);
+    }
+}
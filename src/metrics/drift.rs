@@ -0,0 +1,350 @@
+use crate::config::DriftConfig;
+use crate::metrics::stats::{QuantileSketch, RunningStats};
+use crate::models::ml_model::{FeatureProfile, LinearRegressionModel, ReferenceProfile};
+use anyhow::Result;
+use prometheus::core::{Collector, Desc};
+use prometheus::proto::MetricFamily;
+use prometheus::{GaugeVec, Opts, Registry};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use utoipa::ToSchema;
+
+/// Floor for bin shares in the PSI, so empty bins do not make it infinite
+const MIN_BIN_SHARE: f64 = 1e-4;
+
+/// Drift of one input feature from its training distribution
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FeatureDrift {
+    pub name: String,
+    pub mean: Option<f64>,
+    pub std_dev: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Streaming estimates of the 5th, 25th, 50th, 75th and 95th percentiles
+    pub quantiles: Vec<Option<f64>>,
+    /// Population stability index against the reference bins
+    pub psi: Option<f64>,
+    /// Kolmogorov-Smirnov statistic against the reference bins
+    pub ks: Option<f64>,
+    /// Whether `psi` or `ks` exceeds its configured threshold
+    pub drifted: bool,
+}
+
+/// Input distribution of recent predictions compared to the training data
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DriftReport {
+    pub model_version: String,
+    /// Predictions in the window the statistics describe
+    pub window_samples: u64,
+    /// Whether the model artifact included a reference profile; without
+    /// one, only the statistics are reported
+    pub reference_available: bool,
+    pub features: Vec<FeatureDrift>,
+}
+
+#[derive(Debug, Clone)]
+struct FeatureWindow {
+    stats: RunningStats,
+    quantiles: QuantileSketch,
+    /// Count per reference bin; empty without a reference profile
+    bins: Vec<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct Window {
+    count: u64,
+    features: Vec<FeatureWindow>,
+}
+
+impl Window {
+    fn new(feature_count: usize, reference: Option<&ReferenceProfile>) -> Self {
+        let features = (0..feature_count)
+            .map(|i| FeatureWindow {
+                stats: RunningStats::default(),
+                quantiles: QuantileSketch::default(),
+                bins: reference
+                    .map(|profile| vec![0; profile.features[i].bin_proportions.len()])
+                    .unwrap_or_default(),
+            })
+            .collect();
+        Self { count: 0, features }
+    }
+}
+
+/// Windows for the model currently serving predictions
+struct ModelWindows {
+    model_version: String,
+    feature_names: Vec<String>,
+    reference: Option<ReferenceProfile>,
+    current: Window,
+    /// Last full window, reported until `current` has enough samples
+    previous: Option<Window>,
+}
+
+/// Tracks the distribution of `/predict` inputs in fixed-size windows and
+/// scores it against the model's reference profile
+pub struct DriftMonitor {
+    config: DriftConfig,
+    psi: GaugeVec,
+    ks: GaugeVec,
+    windows: Mutex<Option<ModelWindows>>,
+}
+
+impl DriftMonitor {
+    pub fn new(config: DriftConfig) -> Result<Self> {
+        Ok(Self {
+            config,
+            psi: GaugeVec::new(
+                Opts::new(
+                    "feature_drift_psi",
+                    "Population stability index of recent inputs against the training data",
+                ),
+                &["model_version", "feature"],
+            )?,
+            ks: GaugeVec::new(
+                Opts::new(
+                    "feature_drift_ks",
+                    "Kolmogorov-Smirnov statistic of recent inputs against the training data",
+                ),
+                &["model_version", "feature"],
+            )?,
+            windows: Mutex::new(None),
+        })
+    }
+
+    /// Export the drift gauges from `registry`, refreshed on every scrape
+    pub fn register(self: &Arc<Self>, registry: &Registry) -> Result<()> {
+        registry.register(Box::new(DriftCollector(self.clone())))?;
+        Ok(())
+    }
+
+    /// Record the features of a successful prediction
+    pub fn observe(&self, model: &LinearRegressionModel, features: &[f64]) {
+        let mut windows = self.windows.lock().expect("drift lock poisoned");
+        if windows
+            .as_ref()
+            .is_none_or(|windows| windows.model_version != model.version())
+        {
+            // A different model restarts tracking and drops its stale gauges
+            self.psi.reset();
+            self.ks.reset();
+            let reference = model.reference_profile().cloned();
+            *windows = Some(ModelWindows {
+                model_version: model.version().to_string(),
+                feature_names: model.feature_names().to_vec(),
+                current: Window::new(features.len(), reference.as_ref()),
+                reference,
+                previous: None,
+            });
+        }
+        let windows = windows.as_mut().expect("windows initialized above");
+
+        if windows.current.count >= self.config.window_size {
+            let fresh = Window::new(features.len(), windows.reference.as_ref());
+            windows.previous = Some(std::mem::replace(&mut windows.current, fresh));
+        }
+
+        let current = &mut windows.current;
+        current.count += 1;
+        for (i, (&value, feature)) in features.iter().zip(&mut current.features).enumerate() {
+            feature.stats.observe(value);
+            feature.quantiles.observe(value);
+            if let Some(profile) = &windows.reference {
+                let bin = profile.features[i]
+                    .bin_edges
+                    .partition_point(|&edge| edge < value);
+                feature.bins[bin] += 1;
+            }
+        }
+    }
+
+    /// Drift report for `model`; empty until it has served a prediction
+    pub fn report(&self, model: &LinearRegressionModel) -> DriftReport {
+        self.tracked_report()
+            .filter(|report| report.model_version == model.version())
+            .unwrap_or_else(|| {
+                let window = Window::new(model.feature_names().len(), None);
+                self.build_report(model.version(), model.feature_names(), None, &window)
+            })
+    }
+
+    /// Report for the model whose inputs are being tracked, if any
+    fn tracked_report(&self) -> Option<DriftReport> {
+        let windows = self.windows.lock().expect("drift lock poisoned");
+        let windows = windows.as_ref()?;
+        let window = match &windows.previous {
+            Some(previous) if windows.current.count < self.config.min_samples => previous,
+            _ => &windows.current,
+        };
+        Some(self.build_report(
+            &windows.model_version,
+            &windows.feature_names,
+            windows.reference.as_ref(),
+            window,
+        ))
+    }
+
+    fn build_report(
+        &self,
+        model_version: &str,
+        feature_names: &[String],
+        reference: Option<&ReferenceProfile>,
+        window: &Window,
+    ) -> DriftReport {
+        let scored = window.count >= self.config.min_samples;
+        let features = feature_names
+            .iter()
+            .zip(&window.features)
+            .enumerate()
+            .map(|(i, (name, feature))| {
+                let scores = reference
+                    .filter(|_| scored)
+                    .map(|profile| scores(&profile.features[i], &feature.bins));
+                let psi = scores.map(|(psi, _)| psi);
+                let ks = scores.map(|(_, ks)| ks);
+                FeatureDrift {
+                    name: name.clone(),
+                    mean: feature.stats.mean(),
+                    std_dev: feature.stats.std_dev(),
+                    min: feature.stats.min(),
+                    max: feature.stats.max(),
+                    quantiles: feature.quantiles.estimates(),
+                    psi,
+                    ks,
+                    drifted: psi.is_some_and(|psi| psi > self.config.psi_threshold)
+                        || ks.is_some_and(|ks| ks > self.config.ks_threshold),
+                }
+            })
+            .collect();
+
+        DriftReport {
+            model_version: model_version.to_string(),
+            window_samples: window.count,
+            reference_available: reference.is_some(),
+            features,
+        }
+    }
+}
+
+/// PSI and KS statistic of observed bin counts against the reference shares
+fn scores(reference: &FeatureProfile, bins: &[u64]) -> (f64, f64) {
+    let total: u64 = bins.iter().sum();
+    let reference_total: f64 = reference.bin_proportions.iter().sum();
+    if total == 0 || reference_total <= 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let mut psi = 0.0;
+    let mut ks: f64 = 0.0;
+    let (mut observed_cdf, mut expected_cdf) = (0.0, 0.0);
+    for (&count, &share) in bins.iter().zip(&reference.bin_proportions) {
+        let observed = count as f64 / total as f64;
+        let expected = share / reference_total;
+        let (floored_observed, floored_expected) =
+            (observed.max(MIN_BIN_SHARE), expected.max(MIN_BIN_SHARE));
+        psi += (floored_observed - floored_expected) * (floored_observed / floored_expected).ln();
+
+        observed_cdf += observed;
+        expected_cdf += expected;
+        ks = ks.max((observed_cdf - expected_cdf).abs());
+    }
+    (psi, ks)
+}
+
+/// Sets the drift gauges from the latest report when scraped
+struct DriftCollector(Arc<DriftMonitor>);
+
+impl Collector for DriftCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        [self.0.psi.desc(), self.0.ks.desc()].concat()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let monitor = &self.0;
+        // Only the model that has served predictions is reported
+        if let Some(report) = monitor.tracked_report() {
+            for feature in &report.features {
+                let labels = [report.model_version.as_str(), feature.name.as_str()];
+                if let (Some(psi), Some(ks)) = (feature.psi, feature.ks) {
+                    monitor.psi.with_label_values(&labels).set(psi);
+                    monitor.ks.with_label_values(&labels).set(ks);
+                }
+            }
+        }
+        [monitor.psi.collect(), monitor.ks.collect()].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> FeatureProfile {
+        FeatureProfile {
+            mean: 0.0,
+            std_dev: 1.0,
+            bin_edges: vec![-1.0, 0.0, 1.0],
+            bin_proportions: vec![0.25, 0.25, 0.25, 0.25],
+        }
+    }
+
+    #[test]
+    fn test_scores_zero_for_matching_distribution() {
+        let (psi, ks) = scores(&profile(), &[100, 100, 100, 100]);
+        assert!(psi.abs() < 1e-12);
+        assert!(ks.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_scores_detect_shift() {
+        // Everything moved into the top bin
+        let (psi, ks) = scores(&profile(), &[0, 0, 0, 400]);
+        assert!(psi > 1.0, "psi {}", psi);
+        assert!((ks - 0.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_report_scores_after_min_samples() {
+        let path = std::env::temp_dir().join(format!("model-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            r#"{"version": "v-drift", "weights": [1.0], "bias": 0.0, "feature_names": ["x"],
+                "reference_profile": {"features": [
+                    {"mean": 0.0, "std_dev": 1.0, "bin_edges": [-1.0, 0.0, 1.0], "bin_proportions": [0.25, 0.25, 0.25, 0.25]}
+                ]}}"#,
+        )
+        .unwrap();
+        let model = LinearRegressionModel::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let monitor = DriftMonitor::new(DriftConfig {
+            window_size: 20,
+            min_samples: 10,
+            ..DriftConfig::default()
+        })
+        .unwrap();
+        assert_eq!(monitor.report(&model).window_samples, 0);
+
+        for _ in 0..5 {
+            monitor.observe(&model, &[5.0]);
+        }
+        let report = monitor.report(&model);
+        assert_eq!(report.features[0].mean, Some(5.0));
+        assert_eq!(report.features[0].psi, None);
+
+        for _ in 0..5 {
+            monitor.observe(&model, &[5.0]);
+        }
+        let report = monitor.report(&model);
+        assert_eq!(report.window_samples, 10);
+        assert!(report.reference_available);
+        assert!(report.features[0].drifted);
+        assert!((report.features[0].ks.unwrap() - 0.75).abs() < 1e-12);
+
+        // A new window keeps reporting the full one until it has enough samples
+        for _ in 0..11 {
+            monitor.observe(&model, &[5.0]);
+        }
+        assert_eq!(monitor.report(&model).window_samples, 20);
+    }
+}
@@ -1,12 +1,60 @@
-use crate::metrics::prometheus::record_http_request;
-use axum::{extract::Request, middleware::Next, response::Response};
+use crate::metrics::openmetrics::ExemplarLabels;
+use crate::metrics::prometheus::AppMetrics;
+use crate::request_id::RequestId;
+use crate::telemetry;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use prometheus::Gauge;
+use std::sync::Arc;
 use std::time::Instant;
 
+/// Counts one request in `http_requests_in_flight` until dropped, so requests
+/// that are cancelled (client disconnect, timeout) or panic are released too
+struct InFlightGuard(Gauge);
+
+impl InFlightGuard {
+    fn new(gauge: &Gauge) -> Self {
+        gauge.inc();
+        Self(gauge.clone())
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+/// Label for paths that matched no route, so scanners cannot grow cardinality
+const UNMATCHED_ENDPOINT: &str = "unmatched";
+
 /// Middleware to record HTTP request metrics
-pub async fn metrics_middleware(request: Request, next: Next) -> Response {
+///
+/// Must be added with `Router::layer` so the matched route template (for
+/// example `/models/{name}`) is available as the `endpoint` label.
+pub async fn metrics_middleware(
+    State(metrics): State<Arc<AppMetrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let _in_flight = InFlightGuard::new(&metrics.http_requests_in_flight);
     let start_time = Instant::now();
     let method = request.method().to_string();
-    let uri = request.uri().path().to_string();
+    let endpoint = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ENDPOINT.to_string());
+    let exemplar = ExemplarLabels {
+        trace_id: telemetry::current_trace_id(),
+        request_id: request
+            .extensions()
+            .get::<RequestId>()
+            .map(|id| id.as_str().to_string()),
+    };
 
     // Process the request
     let response = next.run(request).await;
@@ -15,7 +63,93 @@ pub async fn metrics_middleware(request: Request, next: Next) -> Response {
     let duration = start_time.elapsed().as_secs_f64();
     let status = response.status().as_u16();
 
-    record_http_request(&method, &uri, status, duration);
+    metrics.record_http_request(&method, &endpoint, status, duration, &exemplar);
 
     response
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_endpoint_label_is_route_template() {
+        use prometheus::core::Collector;
+
+        let metrics = Arc::new(AppMetrics::new().unwrap());
+        let app = Router::new()
+            .route("/widgets/{id}", get(|| async { "widget" }))
+            .layer(middleware::from_fn_with_state(
+                metrics.clone(),
+                metrics_middleware,
+            ));
+        let count = |endpoint: &str, status: &str| {
+            metrics
+                .http_requests_total
+                .with_label_values(&["GET", endpoint, status])
+                .get()
+        };
+
+        for uri in ["/widgets/1", "/widgets/2", "/wp-admin.php", "/random-scan"] {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        assert_eq!(count("/widgets/{id}", "200"), 2.0);
+        assert_eq!(count("unmatched", "404"), 2.0);
+        let raw_paths_recorded = metrics
+            .http_requests_total
+            .collect()
+            .iter()
+            .flat_map(|family| family.get_metric())
+            .flat_map(|metric| metric.get_label())
+            .any(|label| label.get_value() == "/widgets/1" || label.get_value() == "/wp-admin.php");
+        assert!(!raw_paths_recorded);
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_released_on_completion_cancellation_and_panic() {
+        let metrics = Arc::new(AppMetrics::new().unwrap());
+        let app = Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    "late"
+                }),
+            )
+            .route(
+                "/panic",
+                get(|| async {
+                    panic!("handler failed");
+                    #[allow(unreachable_code)]
+                    ""
+                }),
+            )
+            .layer(middleware::from_fn_with_state(
+                metrics.clone(),
+                metrics_middleware,
+            ));
+        let request = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let in_flight = || metrics.http_requests_in_flight.get();
+
+        app.clone().oneshot(request("/ok")).await.unwrap();
+        assert_eq!(in_flight(), 0.0);
+
+        // Counted while running, released when the request future is dropped
+        let slow = tokio::spawn(app.clone().oneshot(request("/slow")));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(in_flight(), 1.0);
+        slow.abort();
+        assert!(slow.await.unwrap_err().is_cancelled());
+        assert_eq!(in_flight(), 0.0);
+
+        let panicked = tokio::spawn(app.oneshot(request("/panic"))).await;
+        assert!(panicked.unwrap_err().is_panic());
+        assert_eq!(in_flight(), 0.0);
+    }
+}
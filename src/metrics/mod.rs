@@ -1,2 +1,9 @@
+pub mod connections;
+pub mod drift;
 pub mod middleware;
+pub mod openmetrics;
+pub mod output;
 pub mod prometheus;
+pub mod runtime;
+pub mod slo;
+pub mod stats;
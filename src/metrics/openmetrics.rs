@@ -0,0 +1,410 @@
+use prometheus::proto::{Metric, MetricFamily, MetricType};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Content type of the OpenMetrics text exposition
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// OpenMetrics limits the combined length of an exemplar's label names and values
+const MAX_EXEMPLAR_LABEL_CHARS: usize = 128;
+
+/// Whether an `Accept` header asks for OpenMetrics over the classic text format
+pub fn accepts_openmetrics(accept: &str) -> bool {
+    accept.split(',').any(|media_range| {
+        media_range
+            .split(';')
+            .next()
+            .is_some_and(|media_type| media_type.trim() == "application/openmetrics-text")
+    })
+}
+
+/// Labels identifying the request behind an observation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExemplarLabels {
+    pub trace_id: Option<String>,
+    pub request_id: Option<String>,
+}
+
+impl ExemplarLabels {
+    /// Label pairs within the OpenMetrics length limit; the request ID is
+    /// left out when it would exceed it
+    fn pairs(&self) -> Vec<(&'static str, &str)> {
+        let mut pairs = Vec::new();
+        let mut chars = 0;
+        for (name, value) in [
+            ("trace_id", self.trace_id.as_deref()),
+            ("request_id", self.request_id.as_deref()),
+        ] {
+            let Some(value) = value else { continue };
+            let len = name.len() + value.chars().count();
+            if chars + len <= MAX_EXEMPLAR_LABEL_CHARS {
+                chars += len;
+                pairs.push((name, value));
+            }
+        }
+        pairs
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Exemplar {
+    labels: ExemplarLabels,
+    value: f64,
+    timestamp: f64,
+}
+
+/// Metric name, sorted label pairs and bucket upper bound (as bits)
+type ExemplarKey = (String, Vec<(String, String)>, u64);
+
+/// Latest exemplar for every histogram bucket that has one
+///
+/// Bounded by the number of label sets times buckets of the histograms that
+/// record exemplars.
+#[derive(Default)]
+pub struct ExemplarStore {
+    exemplars: Mutex<HashMap<ExemplarKey, Exemplar>>,
+}
+
+impl ExemplarStore {
+    /// Remember `labels` as the exemplar of the bucket `value` falls into
+    ///
+    /// `bounds` are the histogram's bucket upper bounds, without `+Inf`.
+    pub fn observe(
+        &self,
+        metric: &str,
+        metric_labels: &[(&str, &str)],
+        bounds: &[f64],
+        value: f64,
+        labels: &ExemplarLabels,
+    ) {
+        if labels.pairs().is_empty() {
+            return;
+        }
+
+        let upper_bound = bounds
+            .iter()
+            .copied()
+            .find(|bound| value <= *bound)
+            .unwrap_or(f64::INFINITY);
+        let mut metric_labels: Vec<(String, String)> = metric_labels
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        metric_labels.sort();
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        self.exemplars
+            .lock()
+            .expect("exemplar lock poisoned")
+            .insert(
+                (metric.to_string(), metric_labels, upper_bound.to_bits()),
+                Exemplar {
+                    labels: labels.clone(),
+                    value,
+                    timestamp,
+                },
+            );
+    }
+
+    fn get(
+        &self,
+        metric: &str,
+        metric_labels: &[(String, String)],
+        upper_bound: f64,
+    ) -> Option<Exemplar> {
+        let mut metric_labels = metric_labels.to_vec();
+        metric_labels.sort();
+        self.exemplars
+            .lock()
+            .expect("exemplar lock poisoned")
+            .get(&(metric.to_string(), metric_labels, upper_bound.to_bits()))
+            .cloned()
+    }
+}
+
+/// Encode metric families in the OpenMetrics text format, attaching the
+/// stored exemplars to histogram buckets
+pub fn encode(families: &[MetricFamily], exemplars: &ExemplarStore) -> String {
+    let mut out = String::new();
+    for family in families {
+        let name = family.get_name();
+        let (family_name, metric_type) = match family.get_field_type() {
+            // OpenMetrics names the counter family without its `_total` suffix
+            MetricType::COUNTER => (name.strip_suffix("_total").unwrap_or(name), "counter"),
+            MetricType::GAUGE => (name, "gauge"),
+            MetricType::HISTOGRAM => (name, "histogram"),
+            MetricType::SUMMARY => (name, "summary"),
+            MetricType::UNTYPED => (name, "unknown"),
+        };
+
+        let _ = writeln!(out, "# TYPE {} {}", family_name, metric_type);
+        if !family.get_help().is_empty() {
+            let _ = writeln!(out, "# HELP {} {}", family_name, escape(family.get_help()));
+        }
+
+        for metric in family.get_metric() {
+            let labels: Vec<(String, String)> = metric
+                .get_label()
+                .iter()
+                .map(|pair| (pair.get_name().to_string(), pair.get_value().to_string()))
+                .collect();
+            match family.get_field_type() {
+                MetricType::COUNTER => write_sample(
+                    &mut out,
+                    &format!("{}_total", family_name),
+                    &labels,
+                    None,
+                    metric.get_counter().get_value(),
+                ),
+                MetricType::GAUGE => write_sample(
+                    &mut out,
+                    name,
+                    &labels,
+                    None,
+                    metric.get_gauge().get_value(),
+                ),
+                MetricType::UNTYPED => write_sample(
+                    &mut out,
+                    name,
+                    &labels,
+                    None,
+                    metric.get_untyped().get_value(),
+                ),
+                MetricType::HISTOGRAM => {
+                    write_histogram(&mut out, name, &labels, metric, exemplars)
+                }
+                MetricType::SUMMARY => {
+                    let summary = metric.get_summary();
+                    for quantile in summary.get_quantile() {
+                        let mut quantile_labels = labels.clone();
+                        quantile_labels.push((
+                            "quantile".to_string(),
+                            format_value(quantile.get_quantile()),
+                        ));
+                        write_sample(&mut out, name, &quantile_labels, None, quantile.get_value());
+                    }
+                    write_sample(
+                        &mut out,
+                        &format!("{}_sum", name),
+                        &labels,
+                        None,
+                        summary.get_sample_sum(),
+                    );
+                    write_sample(
+                        &mut out,
+                        &format!("{}_count", name),
+                        &labels,
+                        None,
+                        summary.get_sample_count() as f64,
+                    );
+                }
+            }
+        }
+    }
+    out.push_str("# EOF\n");
+    out
+}
+
+fn write_histogram(
+    out: &mut String,
+    name: &str,
+    labels: &[(String, String)],
+    metric: &Metric,
+    exemplars: &ExemplarStore,
+) {
+    let histogram = metric.get_histogram();
+    let bucket_name = format!("{}_bucket", name);
+    let buckets = histogram
+        .get_bucket()
+        .iter()
+        .map(|bucket| (bucket.get_upper_bound(), bucket.get_cumulative_count()))
+        .chain(std::iter::once((
+            f64::INFINITY,
+            histogram.get_sample_count(),
+        )));
+
+    for (upper_bound, count) in buckets {
+        let mut bucket_labels = labels.to_vec();
+        bucket_labels.push(("le".to_string(), format_value(upper_bound)));
+        let exemplar = exemplars.get(name, labels, upper_bound);
+        write_sample(
+            out,
+            &bucket_name,
+            &bucket_labels,
+            exemplar.as_ref(),
+            count as f64,
+        );
+    }
+    write_sample(
+        out,
+        &format!("{}_count", name),
+        labels,
+        None,
+        histogram.get_sample_count() as f64,
+    );
+    // OpenMetrics forbids `_sum` when any bucket threshold is negative, since
+    // the sum is then no longer monotonic
+    if histogram
+        .get_bucket()
+        .iter()
+        .all(|bucket| bucket.get_upper_bound() >= 0.0)
+    {
+        write_sample(
+            out,
+            &format!("{}_sum", name),
+            labels,
+            None,
+            histogram.get_sample_sum(),
+        );
+    }
+}
+
+fn write_sample(
+    out: &mut String,
+    name: &str,
+    labels: &[(String, String)],
+    exemplar: Option<&Exemplar>,
+    value: f64,
+) {
+    out.push_str(name);
+    write_labels(
+        out,
+        labels
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str())),
+    );
+    let _ = write!(out, " {}", format_value(value));
+    if let Some(exemplar) = exemplar {
+        out.push_str(" # ");
+        write_labels(out, exemplar.labels.pairs().into_iter());
+        let _ = write!(
+            out,
+            " {} {:.3}",
+            format_value(exemplar.value),
+            exemplar.timestamp
+        );
+    }
+    out.push('\n');
+}
+
+fn write_labels<'a>(out: &mut String, labels: impl Iterator<Item = (&'a str, &'a str)>) {
+    let pairs: Vec<String> = labels
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+        .collect();
+    if !pairs.is_empty() {
+        let _ = write!(out, "{{{}}}", pairs.join(","));
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else if value.is_nan() {
+        "NaN".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escape backslashes, double quotes and newlines in label values and help text
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::core::Collector;
+    use prometheus::{CounterVec, HistogramOpts, HistogramVec, Opts};
+
+    #[test]
+    fn test_accept_negotiation() {
+        assert!(accepts_openmetrics(
+            "application/openmetrics-text; version=1.0.0,text/plain;q=0.5"
+        ));
+        assert!(!accepts_openmetrics("text/plain; version=0.0.4"));
+        assert!(!accepts_openmetrics("*/*"));
+    }
+
+    #[test]
+    fn test_encode_counter_and_histogram_with_exemplar() {
+        let counter = CounterVec::new(Opts::new("jobs_total", "Jobs \"done\""), &["kind"]).unwrap();
+        counter.with_label_values(&["a"]).inc_by(3.0);
+        let histogram = HistogramVec::new(
+            HistogramOpts::new("latency_seconds", "Latency").buckets(vec![0.1, 1.0]),
+            &["route"],
+        )
+        .unwrap();
+        histogram.with_label_values(&["/x"]).observe(0.5);
+
+        let store = ExemplarStore::default();
+        store.observe(
+            "latency_seconds",
+            &[("route", "/x")],
+            &[0.1, 1.0],
+            0.5,
+            &ExemplarLabels {
+                trace_id: Some("4bf92f3577b34da6a3ce929d0e0e4736".to_string()),
+                request_id: Some("req-1".to_string()),
+            },
+        );
+
+        let families = [counter.collect(), histogram.collect()].concat();
+        let text = encode(&families, &store);
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], "# TYPE jobs counter");
+        assert_eq!(lines[1], "# HELP jobs Jobs \\\"done\\\"");
+        assert_eq!(lines[2], "jobs_total{kind=\"a\"} 3");
+        assert_eq!(
+            lines[5],
+            "latency_seconds_bucket{route=\"/x\",le=\"0.1\"} 0"
+        );
+        assert!(lines[6].starts_with(
+            "latency_seconds_bucket{route=\"/x\",le=\"1\"} 1 # {trace_id=\"4bf92f3577b34da6a3ce929d0e0e4736\",request_id=\"req-1\"} 0.5 "
+        ));
+        assert_eq!(
+            lines[7],
+            "latency_seconds_bucket{route=\"/x\",le=\"+Inf\"} 1"
+        );
+        assert_eq!(lines[8], "latency_seconds_count{route=\"/x\"} 1");
+        assert_eq!(lines[9], "latency_seconds_sum{route=\"/x\"} 0.5");
+        assert_eq!(lines.last(), Some(&"# EOF"));
+    }
+
+    #[test]
+    fn test_no_sum_with_negative_buckets() {
+        let histogram = HistogramVec::new(
+            HistogramOpts::new("prediction_value", "Predictions").buckets(vec![-1.0, 0.0, 1.0]),
+            &[],
+        )
+        .unwrap();
+        histogram.with_label_values(&[]).observe(-0.5);
+
+        let text = encode(&histogram.collect(), &ExemplarStore::default());
+        assert!(text.contains("prediction_value_bucket{le=\"-1\"} 0\n"));
+        assert!(text.contains("prediction_value_count 1\n"));
+        assert!(!text.contains("prediction_value_sum"));
+    }
+
+    #[test]
+    fn test_exemplar_labels_respect_length_limit() {
+        let labels = ExemplarLabels {
+            trace_id: Some("a".repeat(32)),
+            request_id: Some("r".repeat(128)),
+        };
+        assert_eq!(labels.pairs().len(), 1);
+        assert!(ExemplarLabels::default().pairs().is_empty());
+    }
+}
@@ -0,0 +1,327 @@
+use crate::config::OutputDriftConfig;
+use crate::metrics::stats::{QuantileSketch, RunningStats, SKETCH_QUANTILES};
+use anyhow::Result;
+use prometheus::core::{Collector, Desc};
+use prometheus::proto::MetricFamily;
+use prometheus::{GaugeVec, Opts, Registry};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Default)]
+struct OutputWindow {
+    stats: RunningStats,
+    quantiles: QuantileSketch,
+}
+
+impl OutputWindow {
+    fn observe(&mut self, prediction: f64) {
+        self.stats.observe(prediction);
+        self.quantiles.observe(prediction);
+    }
+}
+
+/// How far a window's predictions moved from the baseline window
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OutputShift {
+    /// Difference of means, in baseline standard deviations
+    mean_shift: f64,
+    /// Largest difference of any tracked quantile, in baseline standard deviations
+    quantile_shift: f64,
+    /// Window standard deviation over baseline standard deviation
+    std_dev_ratio: f64,
+}
+
+impl OutputShift {
+    /// `None` until both windows have a spread to compare
+    fn between(baseline: &OutputWindow, window: &OutputWindow) -> Option<Self> {
+        let scale = baseline.stats.std_dev().filter(|std_dev| *std_dev > 0.0)?;
+        let quantile_shift = baseline
+            .quantiles
+            .estimates()
+            .into_iter()
+            .zip(window.quantiles.estimates())
+            .filter_map(|(baseline, current)| Some((current? - baseline?).abs() / scale))
+            .fold(0.0, f64::max);
+        Some(Self {
+            mean_shift: (window.stats.mean()? - baseline.stats.mean()?).abs() / scale,
+            quantile_shift,
+            std_dev_ratio: window.stats.std_dev()? / scale,
+        })
+    }
+}
+
+/// Windows for the model version currently serving predictions
+struct ModelOutputs {
+    model_version: String,
+    /// First full window of this model version
+    baseline: Option<OutputWindow>,
+    current: OutputWindow,
+    /// Last full window, reported until `current` has enough samples
+    previous: Option<OutputWindow>,
+}
+
+/// Tracks the distribution of predicted values in fixed-size windows and
+/// compares it to the model version's first full window
+pub struct OutputMonitor {
+    config: OutputDriftConfig,
+    quantile: GaugeVec,
+    mean_shift: GaugeVec,
+    quantile_shift: GaugeVec,
+    std_dev_ratio: GaugeVec,
+    shifted: GaugeVec,
+    outputs: Mutex<Option<ModelOutputs>>,
+}
+
+impl OutputMonitor {
+    pub fn new(config: OutputDriftConfig) -> Result<Self> {
+        let gauge =
+            |name: &str, help: &str| GaugeVec::new(Opts::new(name, help), &["model_version"]);
+        Ok(Self {
+            config,
+            quantile: GaugeVec::new(
+                Opts::new(
+                    "ml_prediction_value_quantile",
+                    "Streaming quantile estimates of recent predicted values",
+                ),
+                &["model_version", "quantile"],
+            )?,
+            mean_shift: gauge(
+                "ml_prediction_mean_shift",
+                "Shift of the mean predicted value from the baseline window, in baseline standard deviations",
+            )?,
+            quantile_shift: gauge(
+                "ml_prediction_quantile_shift",
+                "Largest shift of a predicted value quantile from the baseline window, in baseline standard deviations",
+            )?,
+            std_dev_ratio: gauge(
+                "ml_prediction_std_dev_ratio",
+                "Standard deviation of recent predicted values over that of the baseline window",
+            )?,
+            shifted: gauge(
+                "ml_prediction_output_shifted",
+                "1 when recent predicted values exceed a shift threshold against the baseline window",
+            )?,
+            outputs: Mutex::new(None),
+        })
+    }
+
+    /// Export the output gauges from `registry`, refreshed on every scrape
+    pub fn register(self: &Arc<Self>, registry: &Registry) -> Result<()> {
+        registry.register(Box::new(OutputCollector(self.clone())))?;
+        Ok(())
+    }
+
+    /// Record the value of a successful prediction
+    pub fn observe(&self, model_version: &str, prediction: f64) {
+        let mut outputs = self.outputs.lock().expect("output lock poisoned");
+        if outputs
+            .as_ref()
+            .is_none_or(|outputs| outputs.model_version != model_version)
+        {
+            // A different model gets a new baseline and drops its stale gauges
+            for gauge in [
+                &self.quantile,
+                &self.mean_shift,
+                &self.quantile_shift,
+                &self.std_dev_ratio,
+                &self.shifted,
+            ] {
+                gauge.reset();
+            }
+            *outputs = Some(ModelOutputs {
+                model_version: model_version.to_string(),
+                baseline: None,
+                current: OutputWindow::default(),
+                previous: None,
+            });
+        }
+        let outputs = outputs.as_mut().expect("outputs initialized above");
+
+        if outputs.current.stats.count() >= self.config.window_size {
+            let full = std::mem::take(&mut outputs.current);
+            match &outputs.baseline {
+                None => outputs.baseline = Some(full.clone()),
+                Some(baseline) => {
+                    if let Some(shift) =
+                        OutputShift::between(baseline, &full).filter(|s| self.is_shifted(s))
+                    {
+                        tracing::warn!(
+                            model_version,
+                            mean_shift = shift.mean_shift,
+                            quantile_shift = shift.quantile_shift,
+                            std_dev_ratio = shift.std_dev_ratio,
+                            "Predicted values shifted from the baseline window"
+                        );
+                    }
+                }
+            }
+            outputs.previous = Some(full);
+        }
+        outputs.current.observe(prediction);
+    }
+
+    fn is_shifted(&self, shift: &OutputShift) -> bool {
+        let ratio = self.config.std_dev_ratio_threshold;
+        shift.mean_shift > self.config.mean_shift_threshold
+            || shift.quantile_shift > self.config.quantile_shift_threshold
+            || shift.std_dev_ratio > ratio
+            || shift.std_dev_ratio < 1.0 / ratio
+    }
+
+    /// Model version, quantile estimates and shift of the reported window
+    fn status(&self) -> Option<(String, Vec<Option<f64>>, Option<OutputShift>)> {
+        let outputs = self.outputs.lock().expect("output lock poisoned");
+        let outputs = outputs.as_ref()?;
+        let window = match &outputs.previous {
+            Some(previous) if outputs.current.stats.count() < self.config.min_samples => previous,
+            _ => &outputs.current,
+        };
+        let shift = outputs
+            .baseline
+            .as_ref()
+            .filter(|_| window.stats.count() >= self.config.min_samples)
+            .and_then(|baseline| OutputShift::between(baseline, window));
+        Some((
+            outputs.model_version.clone(),
+            window.quantiles.estimates(),
+            shift,
+        ))
+    }
+}
+
+/// Sets the output gauges from the reported window when scraped
+struct OutputCollector(Arc<OutputMonitor>);
+
+impl Collector for OutputCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        let monitor = &self.0;
+        [
+            monitor.quantile.desc(),
+            monitor.mean_shift.desc(),
+            monitor.quantile_shift.desc(),
+            monitor.std_dev_ratio.desc(),
+            monitor.shifted.desc(),
+        ]
+        .concat()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let monitor = &self.0;
+        if let Some((model_version, estimates, shift)) = monitor.status() {
+            for (p, estimate) in SKETCH_QUANTILES.iter().zip(estimates) {
+                if let Some(estimate) = estimate {
+                    monitor
+                        .quantile
+                        .with_label_values(&[model_version.as_str(), &p.to_string()])
+                        .set(estimate);
+                }
+            }
+            if let Some(shift) = shift {
+                let labels = [model_version.as_str()];
+                monitor
+                    .mean_shift
+                    .with_label_values(&labels)
+                    .set(shift.mean_shift);
+                monitor
+                    .quantile_shift
+                    .with_label_values(&labels)
+                    .set(shift.quantile_shift);
+                monitor
+                    .std_dev_ratio
+                    .with_label_values(&labels)
+                    .set(shift.std_dev_ratio);
+                monitor
+                    .shifted
+                    .with_label_values(&labels)
+                    .set(if monitor.is_shifted(&shift) { 1.0 } else { 0.0 });
+            }
+        }
+        [
+            monitor.quantile.collect(),
+            monitor.mean_shift.collect(),
+            monitor.quantile_shift.collect(),
+            monitor.std_dev_ratio.collect(),
+            monitor.shifted.collect(),
+        ]
+        .concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_monitor() -> OutputMonitor {
+        OutputMonitor::new(OutputDriftConfig {
+            window_size: 100,
+            min_samples: 50,
+            ..OutputDriftConfig::default()
+        })
+        .unwrap()
+    }
+
+    /// Evenly spread values around `center`
+    fn observe_window(monitor: &OutputMonitor, center: f64, spread: f64, count: usize) {
+        for i in 0..count {
+            let offset = (i % 10) as f64 / 9.0 - 0.5;
+            monitor.observe("v1", center + offset * spread);
+        }
+    }
+
+    #[test]
+    fn test_no_shift_without_baseline() {
+        let monitor = new_monitor();
+        assert!(monitor.status().is_none());
+
+        observe_window(&monitor, 0.0, 1.0, 60);
+        let (model_version, estimates, shift) = monitor.status().unwrap();
+        assert_eq!(model_version, "v1");
+        assert!(estimates.iter().all(Option::is_some));
+        assert_eq!(shift, None);
+    }
+
+    #[test]
+    fn test_stable_outputs_are_not_shifted() {
+        let monitor = new_monitor();
+        observe_window(&monitor, 0.0, 1.0, 100);
+        observe_window(&monitor, 0.0, 1.0, 60);
+
+        let (_, _, shift) = monitor.status().unwrap();
+        let shift = shift.unwrap();
+        assert!(shift.mean_shift < 1e-9, "{:?}", shift);
+        assert!((shift.std_dev_ratio - 1.0).abs() < 0.01);
+        assert!(!monitor.is_shifted(&shift));
+    }
+
+    #[test]
+    fn test_mean_and_spread_shifts_are_flagged() {
+        let monitor = new_monitor();
+        observe_window(&monitor, 0.0, 1.0, 100);
+        observe_window(&monitor, 5.0, 1.0, 60);
+        let (_, _, shift) = monitor.status().unwrap();
+        let shift = shift.unwrap();
+        assert!(shift.mean_shift > 10.0);
+        assert!(shift.quantile_shift > 10.0);
+        assert!(monitor.is_shifted(&shift));
+
+        // A model stuck on one value collapses the spread
+        let monitor = new_monitor();
+        observe_window(&monitor, 0.0, 1.0, 100);
+        observe_window(&monitor, 0.0, 0.0, 60);
+        let (_, _, shift) = monitor.status().unwrap();
+        let shift = shift.unwrap();
+        assert_eq!(shift.std_dev_ratio, 0.0);
+        assert!(monitor.is_shifted(&shift));
+    }
+
+    #[test]
+    fn test_new_model_version_resets_baseline() {
+        let monitor = new_monitor();
+        observe_window(&monitor, 0.0, 1.0, 160);
+        assert!(monitor.status().unwrap().2.is_some());
+
+        monitor.observe("v2", 1.0);
+        let (model_version, _, shift) = monitor.status().unwrap();
+        assert_eq!(model_version, "v2");
+        assert_eq!(shift, None);
+    }
+}
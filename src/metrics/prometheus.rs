@@ -1,93 +1,393 @@
-use axum::{http::StatusCode, response::Response};
-use lazy_static::lazy_static;
+use crate::app::AppState;
+use crate::config::MetricsConfig;
+use crate::metrics::openmetrics::{self, ExemplarLabels, ExemplarStore};
+use crate::metrics::runtime::TokioCollector;
+use crate::metrics::slo::SloTracker;
+use crate::models::ml_model::LinearRegressionModel;
+use anyhow::Result;
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::Response,
+};
 use prometheus::{
-    register_counter_vec, register_gauge, register_histogram_vec, CounterVec, Encoder, Gauge,
-    HistogramVec, TextEncoder,
+    proto::MetricFamily, CounterVec, Encoder, Gauge, HistogramOpts, HistogramVec, Opts, Registry,
+    TextEncoder,
 };
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
-lazy_static! {
-    /// HTTP requests total counter with method and endpoint labels
-    pub static ref HTTP_REQUESTS_TOTAL: CounterVec = register_counter_vec!(
-        "http_requests_total",
-        "Total number of HTTP requests processed",
-        &["method", "endpoint", "status"]
-    )
-    .expect("Failed to create HTTP_REQUESTS_TOTAL metric");
-
-    /// HTTP request duration histogram with method and endpoint labels
-    pub static ref HTTP_REQUEST_DURATION_SECONDS: HistogramVec = register_histogram_vec!(
-        "http_request_duration_seconds",
-        "HTTP request latency in seconds",
-        &["method", "endpoint"],
-        vec![0.001, 0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
-    )
-    .expect("Failed to create HTTP_REQUEST_DURATION_SECONDS metric");
-
-    /// ML predictions total counter
-    pub static ref ML_PREDICTIONS_TOTAL: CounterVec = register_counter_vec!(
-        "ml_predictions_total",
-        "Total number of ML predictions made",
-        &["model_version", "status"]
-    )
-    .expect("Failed to create ML_PREDICTIONS_TOTAL metric");
-
-    /// ML prediction confidence histogram
-    pub static ref ML_PREDICTION_CONFIDENCE: HistogramVec = register_histogram_vec!(
-        "ml_prediction_confidence",
-        "Distribution of ML prediction confidence scores",
-        &["model_version"],
-        vec![0.85, 0.87, 0.89, 0.91, 0.93, 0.95, 0.97, 0.99, 1.0]
-    )
-    .expect("Failed to create ML_PREDICTION_CONFIDENCE metric");
-
-    /// Current active connections gauge
-    pub static ref ACTIVE_CONNECTIONS: Gauge = register_gauge!(
-        "active_connections_total",
-        "Number of active connections"
-    )
-    .expect("Failed to create ACTIVE_CONNECTIONS metric");
-
-    /// Service uptime in seconds
-    pub static ref SERVICE_UPTIME_SECONDS: Gauge = register_gauge!(
-        "service_uptime_seconds",
-        "Service uptime in seconds since start"
-    )
-    .expect("Failed to create SERVICE_UPTIME_SECONDS metric");
-}
+/// Most distinct `(method, endpoint, status)` label sets recorded for HTTP
+/// requests; further new endpoints are recorded as `overflow`
+pub const MAX_HTTP_LABEL_SETS: usize = 1000;
 
-/// Setup metrics recorder and start background tasks
-pub fn setup_metrics_recorder() -> anyhow::Result<()> {
-    // Initialize service start time
-    SERVICE_UPTIME_SECONDS.set(0.0);
+/// Default `http_request_duration_seconds` bucket bounds
+const DEFAULT_DURATION_BUCKETS: [f64; 11] = [
+    0.001, 0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
 
-    // Initialize active connections counter
-    set_active_connections(0.0);
+/// Default `ml_prediction_value` bucket bounds, symmetric around zero since
+/// predictions are unbounded
+const DEFAULT_PREDICTION_BUCKETS: [f64; 13] = [
+    -100.0, -50.0, -10.0, -5.0, -1.0, -0.5, 0.0, 0.5, 1.0, 5.0, 10.0, 50.0, 100.0,
+];
 
-    // Start uptime tracking in background
-    tokio::spawn(update_uptime_metrics());
+/// Default `ml_prediction_confidence` buckets split the model's confidence
+/// range into this many equal steps
+const CONFIDENCE_BUCKET_STEPS: usize = 10;
 
-    tracing::info!("Prometheus metrics recorder initialized");
-    Ok(())
+/// Service metrics registered in a registry owned by this instance
+///
+/// Each `AppState` gets its own, so app instances (and tests) running in the
+/// same process never see each other's values. The registry also exports
+/// process metrics (Linux only) and Tokio runtime metrics.
+pub struct AppMetrics {
+    registry: Registry,
+    start_time: Instant,
+    http_label_sets: LabelSetGuard,
+    duration_buckets: Vec<f64>,
+    confidence_buckets: Vec<f64>,
+    prediction_buckets: Vec<f64>,
+    /// Requests behind recent latency, confidence and prediction observations, served
+    /// with the OpenMetrics format
+    exemplars: ExemplarStore,
+    /// Fed every recorded request when SLO tracking is enabled
+    slo: Option<Arc<SloTracker>>,
+
+    /// HTTP requests by method, route template and status
+    pub http_requests_total: CounterVec,
+    /// HTTP request latency by method and route template
+    pub http_request_duration_seconds: HistogramVec,
+    /// Requests rejected before reaching a handler, by reason
+    pub http_requests_rejected_total: CounterVec,
+    /// Requests rejected by the rate limiter, by client tier
+    pub rate_limited_requests_total: CounterVec,
+    /// Prediction audit records that were not written, by reason
+    pub prediction_audit_dropped_total: CounterVec,
+    /// ML predictions by model version and outcome
+    pub ml_predictions_total: CounterVec,
+    /// Confidence of successful predictions by model version
+    pub ml_prediction_confidence: HistogramVec,
+    /// Predicted values of successful predictions by model version
+    pub ml_prediction_value: HistogramVec,
+    /// Requests with inputs outside the training data, by model version and
+    /// whether they were flagged or rejected
+    pub ml_prediction_ood_total: CounterVec,
+    /// Open TCP connections, maintained by the listener
+    pub active_connections: Gauge,
+    /// Requests being handled, maintained by the metrics middleware
+    pub http_requests_in_flight: Gauge,
+    /// Seconds since this instance was created, updated on every scrape
+    pub service_uptime_seconds: Gauge,
 }
 
-/// Background task to update uptime metrics
-async fn update_uptime_metrics() {
-    let start_time = std::time::Instant::now();
+impl AppMetrics {
+    /// Metrics with the default histogram buckets
+    pub fn new() -> Result<Self> {
+        Self::from_config(&MetricsConfig::default())
+    }
+
+    pub fn from_config(config: &MetricsConfig) -> Result<Self> {
+        let registry = Registry::new();
+        let duration_buckets = config
+            .http_request_duration_seconds
+            .bucket_bounds()
+            .unwrap_or_else(|| DEFAULT_DURATION_BUCKETS.to_vec());
+        let confidence_buckets = config
+            .ml_prediction_confidence
+            .bucket_bounds()
+            .unwrap_or_else(default_confidence_buckets);
+        let prediction_buckets = config
+            .ml_prediction_value
+            .bucket_bounds()
+            .unwrap_or_else(|| DEFAULT_PREDICTION_BUCKETS.to_vec());
+
+        let http_requests_total = CounterVec::new(
+            Opts::new(
+                "http_requests_total",
+                "Total number of HTTP requests processed",
+            ),
+            &["method", "endpoint", "status"],
+        )?;
+        let http_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "HTTP request latency in seconds",
+            )
+            .buckets(duration_buckets.clone()),
+            &["method", "endpoint"],
+        )?;
+        let http_requests_rejected_total = CounterVec::new(
+            Opts::new(
+                "http_requests_rejected_total",
+                "Total number of HTTP requests rejected by timeouts, body limits or load shedding",
+            ),
+            &["reason"],
+        )?;
+        let rate_limited_requests_total = CounterVec::new(
+            Opts::new(
+                "rate_limited_requests_total",
+                "Total number of requests rejected by the rate limiter",
+            ),
+            &["tier"],
+        )?;
+        let prediction_audit_dropped_total = CounterVec::new(
+            Opts::new(
+                "prediction_audit_dropped_total",
+                "Total number of prediction audit records dropped instead of written",
+            ),
+            &["reason"],
+        )?;
+        let ml_predictions_total = CounterVec::new(
+            Opts::new(
+                "ml_predictions_total",
+                "Total number of ML predictions made",
+            ),
+            &["model_version", "status"],
+        )?;
+        let ml_prediction_confidence = HistogramVec::new(
+            HistogramOpts::new(
+                "ml_prediction_confidence",
+                "Distribution of ML prediction confidence scores",
+            )
+            .buckets(confidence_buckets.clone()),
+            &["model_version"],
+        )?;
+        let ml_prediction_value = HistogramVec::new(
+            HistogramOpts::new("ml_prediction_value", "Distribution of ML predicted values")
+                .buckets(prediction_buckets.clone()),
+            &["model_version"],
+        )?;
+        let ml_prediction_ood_total = CounterVec::new(
+            Opts::new(
+                "ml_prediction_ood_total",
+                "Total number of prediction requests with inputs outside the training data",
+            ),
+            &["model_version", "action"],
+        )?;
+        let active_connections =
+            Gauge::new("active_connections_total", "Number of open TCP connections")?;
+        let http_requests_in_flight = Gauge::new(
+            "http_requests_in_flight",
+            "Number of HTTP requests currently being handled",
+        )?;
+        let service_uptime_seconds = Gauge::new(
+            "service_uptime_seconds",
+            "Service uptime in seconds since start",
+        )?;
+
+        registry.register(Box::new(http_requests_total.clone()))?;
+        registry.register(Box::new(http_request_duration_seconds.clone()))?;
+        registry.register(Box::new(http_requests_rejected_total.clone()))?;
+        registry.register(Box::new(rate_limited_requests_total.clone()))?;
+        registry.register(Box::new(prediction_audit_dropped_total.clone()))?;
+        registry.register(Box::new(ml_predictions_total.clone()))?;
+        registry.register(Box::new(ml_prediction_confidence.clone()))?;
+        registry.register(Box::new(ml_prediction_value.clone()))?;
+        registry.register(Box::new(ml_prediction_ood_total.clone()))?;
+        registry.register(Box::new(active_connections.clone()))?;
+        registry.register(Box::new(http_requests_in_flight.clone()))?;
+        registry.register(Box::new(service_uptime_seconds.clone()))?;
+        registry.register(Box::new(TokioCollector::new()?))?;
+        #[cfg(target_os = "linux")]
+        registry.register(Box::new(
+            prometheus::process_collector::ProcessCollector::for_self(),
+        ))?;
+
+        Ok(Self {
+            registry,
+            start_time: Instant::now(),
+            http_label_sets: LabelSetGuard::new(MAX_HTTP_LABEL_SETS),
+            duration_buckets,
+            confidence_buckets,
+            prediction_buckets,
+            exemplars: ExemplarStore::default(),
+            slo: None,
+            http_requests_total,
+            http_request_duration_seconds,
+            http_requests_rejected_total,
+            rate_limited_requests_total,
+            prediction_audit_dropped_total,
+            ml_predictions_total,
+            ml_prediction_confidence,
+            ml_prediction_value,
+            ml_prediction_ood_total,
+            active_connections,
+            http_requests_in_flight,
+            service_uptime_seconds,
+        })
+    }
+
+    /// Feed recorded requests to `slo` and export its gauges
+    pub fn track_slo(&mut self, slo: Arc<SloTracker>) -> Result<()> {
+        slo.register(&self.registry)?;
+        self.slo = Some(slo);
+        Ok(())
+    }
+
+    /// Registry holding every metric of this instance
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Snapshot of all metric families, with uptime brought up to date
+    pub fn gather(&self) -> Vec<MetricFamily> {
+        self.service_uptime_seconds
+            .set(self.start_time.elapsed().as_secs_f64());
+        self.registry.gather()
+    }
+
+    /// All metrics in the OpenMetrics text format, with exemplars
+    pub fn encode_openmetrics(&self) -> String {
+        openmetrics::encode(&self.gather(), &self.exemplars)
+    }
+
+    /// Record HTTP request metrics
+    ///
+    /// `endpoint` must be a route template or `unmatched`, never a raw path.
+    /// `exemplar` identifies the request for the latency bucket it lands in.
+    pub fn record_http_request(
+        &self,
+        method: &str,
+        endpoint: &str,
+        status: u16,
+        duration: f64,
+        exemplar: &ExemplarLabels,
+    ) {
+        let status_str = status.to_string();
+        let method = method_label(method);
+        if let Some(slo) = &self.slo {
+            slo.record(method, endpoint, status, duration);
+        }
+        let endpoint = if self.http_label_sets.admit(&[method, endpoint, &status_str]) {
+            endpoint
+        } else {
+            tracing::debug!(
+                endpoint,
+                "HTTP metric label cap reached, recording as overflow"
+            );
+            "overflow"
+        };
+
+        self.http_requests_total
+            .with_label_values(&[method, endpoint, &status_str])
+            .inc();
+
+        self.http_request_duration_seconds
+            .with_label_values(&[method, endpoint])
+            .observe(duration);
+        self.exemplars.observe(
+            "http_request_duration_seconds",
+            &[("method", method), ("endpoint", endpoint)],
+            &self.duration_buckets,
+            duration,
+            exemplar,
+        );
+    }
+
+    /// Record a request rejected by a timeout, body limit or load shedding
+    pub fn record_rejected_request(&self, reason: &str) {
+        self.http_requests_rejected_total
+            .with_label_values(&[reason])
+            .inc();
+    }
+
+    /// Record a request rejected by the rate limiter
+    pub fn record_rate_limited(&self, tier: &str) {
+        self.rate_limited_requests_total
+            .with_label_values(&[tier])
+            .inc();
+    }
+
+    /// Record a prediction audit record that could not be written
+    pub fn record_audit_dropped(&self, reason: &str) {
+        self.prediction_audit_dropped_total
+            .with_label_values(&[reason])
+            .inc();
+    }
+
+    /// Record ML prediction metrics
+    ///
+    /// `prediction` and `confidence` are only recorded when `success` is set.
+    pub fn record_ml_prediction(
+        &self,
+        model_version: &str,
+        prediction: f64,
+        confidence: f64,
+        success: bool,
+        exemplar: &ExemplarLabels,
+    ) {
+        let status = if success { "success" } else { "error" };
+
+        self.ml_predictions_total
+            .with_label_values(&[model_version, status])
+            .inc();
+
+        if success {
+            self.ml_prediction_confidence
+                .with_label_values(&[model_version])
+                .observe(confidence);
+            self.exemplars.observe(
+                "ml_prediction_confidence",
+                &[("model_version", model_version)],
+                &self.confidence_buckets,
+                confidence,
+                exemplar,
+            );
+            self.ml_prediction_value
+                .with_label_values(&[model_version])
+                .observe(prediction);
+            self.exemplars.observe(
+                "ml_prediction_value",
+                &[("model_version", model_version)],
+                &self.prediction_buckets,
+                prediction,
+                exemplar,
+            );
+        }
+    }
 
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1));
-    loop {
-        interval.tick().await;
-        let uptime = start_time.elapsed().as_secs_f64();
-        SERVICE_UPTIME_SECONDS.set(uptime);
+    /// Record a request whose inputs fell outside the training data
+    pub fn record_ood(&self, model_version: &str, rejected: bool) {
+        let action = if rejected { "rejected" } else { "flagged" };
+        self.ml_prediction_ood_total
+            .with_label_values(&[model_version, action])
+            .inc();
     }
 }
 
 /// Metrics endpoint handler
 ///
-/// Returns Prometheus-formatted metrics
-pub async fn metrics_handler() -> Result<Response<String>, StatusCode> {
+/// Returns Prometheus-formatted metrics, or OpenMetrics with exemplars when
+/// the `Accept` header asks for `application/openmetrics-text`
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "observability",
+    responses((status = 200, description = "Prometheus or OpenMetrics text exposition", content(
+        (String = "text/plain"),
+        (String = "application/openmetrics-text")
+    )))
+)]
+pub async fn metrics_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response<String>, StatusCode> {
+    let wants_openmetrics = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(openmetrics::accepts_openmetrics);
+    if wants_openmetrics {
+        return Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, openmetrics::OPENMETRICS_CONTENT_TYPE)
+            .body(state.metrics.encode_openmetrics())
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
     let encoder = TextEncoder::new();
-    let metric_families = prometheus::gather();
+    let metric_families = state.metrics.gather();
 
     match encoder.encode_to_string(&metric_families) {
         Ok(metrics_text) => {
@@ -106,37 +406,59 @@ pub async fn metrics_handler() -> Result<Response<String>, StatusCode> {
     }
 }
 
-/// Record HTTP request metrics
-pub fn record_http_request(method: &str, endpoint: &str, status: u16, duration: f64) {
-    let status_str = status.to_string();
-
-    HTTP_REQUESTS_TOTAL
-        .with_label_values(&[method, endpoint, &status_str])
-        .inc();
-
-    HTTP_REQUEST_DURATION_SECONDS
-        .with_label_values(&[method, endpoint])
-        .observe(duration);
+/// Caps how many distinct label sets a metric family can grow to
+pub struct LabelSetGuard {
+    max: usize,
+    seen: RwLock<HashSet<Vec<String>>>,
 }
 
-/// Record ML prediction metrics
-pub fn record_ml_prediction(model_version: &str, confidence: f64, success: bool) {
-    let status = if success { "success" } else { "error" };
+impl LabelSetGuard {
+    pub fn new(max: usize) -> Self {
+        Self {
+            max,
+            seen: RwLock::new(HashSet::new()),
+        }
+    }
 
-    ML_PREDICTIONS_TOTAL
-        .with_label_values(&[model_version, status])
-        .inc();
+    /// Whether `labels` is already recorded or still fits under the cap
+    pub fn admit(&self, labels: &[&str]) -> bool {
+        let key: Vec<String> = labels.iter().map(|label| label.to_string()).collect();
+        if self
+            .seen
+            .read()
+            .expect("label set lock poisoned")
+            .contains(&key)
+        {
+            return true;
+        }
 
-    if success {
-        ML_PREDICTION_CONFIDENCE
-            .with_label_values(&[model_version])
-            .observe(confidence);
+        let mut seen = self.seen.write().expect("label set lock poisoned");
+        if seen.len() >= self.max && !seen.contains(&key) {
+            return false;
+        }
+        seen.insert(key);
+        true
     }
 }
 
-/// Update active connections gauge
-pub fn set_active_connections(count: f64) {
-    ACTIVE_CONNECTIONS.set(count);
+/// Equal steps across the model's confidence range, including both ends
+fn default_confidence_buckets() -> Vec<f64> {
+    let (min, max) = LinearRegressionModel::confidence_range();
+    let step = (max - min) / CONFIDENCE_BUCKET_STEPS as f64;
+    (0..CONFIDENCE_BUCKET_STEPS)
+        .map(|i| min + step * i as f64)
+        .chain(std::iter::once(max))
+        .collect()
+}
+
+/// Methods outside the standard set collapse to `other`
+pub fn method_label(method: &str) -> &str {
+    match method {
+        "GET" | "POST" | "PUT" | "DELETE" | "PATCH" | "HEAD" | "OPTIONS" | "CONNECT" | "TRACE" => {
+            method
+        }
+        _ => "other",
+    }
 }
 
 #[cfg(test)]
@@ -145,26 +467,155 @@ mod tests {
 
     #[test]
     fn test_record_http_request() {
-        record_http_request("GET", "/health", 200, 0.001);
-        // Verify metric was recorded (basic smoke test)
-        assert!(HTTP_REQUESTS_TOTAL
-            .get_metric_with_label_values(&["GET", "/health", "200"])
-            .is_ok());
+        let metrics = AppMetrics::new().unwrap();
+        metrics.record_http_request("GET", "/health", 200, 0.001, &ExemplarLabels::default());
+        metrics.record_http_request("GET", "/health", 200, 0.002, &ExemplarLabels::default());
+        metrics.record_http_request(
+            "PROPFIND",
+            "/health",
+            405,
+            0.001,
+            &ExemplarLabels::default(),
+        );
+
+        let total = |labels: &[&str]| metrics.http_requests_total.with_label_values(labels).get();
+        assert_eq!(total(&["GET", "/health", "200"]), 2.0);
+        assert_eq!(total(&["other", "/health", "405"]), 1.0);
+        assert_eq!(
+            metrics
+                .http_request_duration_seconds
+                .with_label_values(&["GET", "/health"])
+                .get_sample_count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_instances_are_isolated() {
+        let first = AppMetrics::new().unwrap();
+        let second = AppMetrics::new().unwrap();
+        first.record_rejected_request("timeout");
+
+        assert_eq!(
+            first
+                .http_requests_rejected_total
+                .with_label_values(&["timeout"])
+                .get(),
+            1.0
+        );
+        assert_eq!(
+            second
+                .http_requests_rejected_total
+                .with_label_values(&["timeout"])
+                .get(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_label_set_guard_caps_combinations() {
+        let guard = LabelSetGuard::new(2);
+
+        assert!(guard.admit(&["GET", "/a", "200"]));
+        assert!(guard.admit(&["GET", "/b", "200"]));
+        assert!(!guard.admit(&["GET", "/c", "200"]));
+        // Already-recorded sets keep being admitted
+        assert!(guard.admit(&["GET", "/a", "200"]));
+    }
+
+    #[test]
+    fn test_unknown_methods_collapse() {
+        assert_eq!(method_label("GET"), "GET");
+        assert_eq!(method_label("PROPFIND"), "other");
     }
 
     #[test]
     fn test_record_ml_prediction() {
-        record_ml_prediction("v1.0.0", 0.95, true);
-        // Verify metric was recorded (basic smoke test)
-        assert!(ML_PREDICTIONS_TOTAL
-            .get_metric_with_label_values(&["v1.0.0", "success"])
-            .is_ok());
+        let metrics = AppMetrics::new().unwrap();
+        metrics.record_ml_prediction("v1.0.0", 3.5, 0.95, true, &ExemplarLabels::default());
+        metrics.record_ml_prediction("v1.0.0", 0.0, 0.0, false, &ExemplarLabels::default());
+
+        let total = |status: &str| {
+            metrics
+                .ml_predictions_total
+                .with_label_values(&["v1.0.0", status])
+                .get()
+        };
+        assert_eq!(total("success"), 1.0);
+        assert_eq!(total("error"), 1.0);
+        // Only successful predictions contribute confidence and value samples
+        assert_eq!(
+            metrics
+                .ml_prediction_confidence
+                .with_label_values(&["v1.0.0"])
+                .get_sample_count(),
+            1
+        );
+        let value = metrics.ml_prediction_value.with_label_values(&["v1.0.0"]);
+        assert_eq!(value.get_sample_count(), 1);
+        assert_eq!(value.get_sample_sum(), 3.5);
+
+        metrics.record_ood("v1.0.0", false);
+        metrics.record_ood("v1.0.0", true);
+        metrics.record_ood("v1.0.0", true);
+        let ood = |action: &str| {
+            metrics
+                .ml_prediction_ood_total
+                .with_label_values(&["v1.0.0", action])
+                .get()
+        };
+        assert_eq!(ood("flagged"), 1.0);
+        assert_eq!(ood("rejected"), 2.0);
     }
 
     #[test]
-    fn test_set_active_connections() {
-        set_active_connections(5.0);
-        // Verify gauge was set
-        assert!((ACTIVE_CONNECTIONS.get() - 5.0).abs() < f64::EPSILON);
+    fn test_histogram_buckets_from_config() {
+        use crate::config::HistogramConfig;
+        use prometheus::core::Collector;
+
+        let bounds = |histogram: &HistogramVec, label: &str| -> Vec<f64> {
+            histogram.with_label_values(&[label]).observe(0.0);
+            histogram.collect()[0].get_metric()[0]
+                .get_histogram()
+                .get_bucket()
+                .iter()
+                .map(|bucket| bucket.get_upper_bound())
+                .collect()
+        };
+
+        let defaults = AppMetrics::new().unwrap();
+        let confidence = bounds(&defaults.ml_prediction_confidence, "v1");
+        let (min, max) = LinearRegressionModel::confidence_range();
+        assert_eq!(confidence.len(), CONFIDENCE_BUCKET_STEPS + 1);
+        assert_eq!(confidence.first(), Some(&min));
+        assert_eq!(confidence.last(), Some(&max));
+
+        let config = MetricsConfig {
+            ml_prediction_confidence: HistogramConfig {
+                buckets: Some(vec![0.25, 0.5, 0.75]),
+                exponential: None,
+            },
+            ..MetricsConfig::default()
+        };
+        let metrics = AppMetrics::from_config(&config).unwrap();
+        assert_eq!(
+            bounds(&metrics.ml_prediction_confidence, "v1"),
+            vec![0.25, 0.5, 0.75]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gather_includes_uptime_process_and_runtime() {
+        let metrics = AppMetrics::new().unwrap();
+        let families = metrics.gather();
+        let has = |name: &str| families.iter().any(|family| family.get_name() == name);
+
+        assert!(has("service_uptime_seconds"));
+        assert!(metrics.service_uptime_seconds.get() >= 0.0);
+        assert!(has("tokio_alive_tasks"));
+        if cfg!(target_os = "linux") {
+            assert!(has("process_resident_memory_bytes"));
+            assert!(has("process_threads"));
+        }
     }
 }
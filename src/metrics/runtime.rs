@@ -0,0 +1,113 @@
+use anyhow::Result;
+use prometheus::core::{Collector, Desc};
+use prometheus::proto::MetricFamily;
+use prometheus::{Counter, IntGauge, Opts};
+use std::sync::Mutex;
+use tokio::runtime::Handle;
+
+/// Tokio runtime metrics, read from the runtime serving the scrape
+///
+/// Worker busy time is exported as a counter; the busy ratio is
+/// `rate(tokio_worker_busy_seconds_total[1m]) / tokio_workers`.
+pub struct TokioCollector {
+    workers: IntGauge,
+    alive_tasks: IntGauge,
+    global_queue_depth: IntGauge,
+    busy_seconds: Counter,
+    /// Serializes scrapes so the busy counter is advanced exactly once per
+    /// unit of busy time
+    busy_lock: Mutex<()>,
+}
+
+impl TokioCollector {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            workers: IntGauge::new("tokio_workers", "Number of Tokio runtime worker threads")?,
+            alive_tasks: IntGauge::new(
+                "tokio_alive_tasks",
+                "Number of tasks alive in the Tokio runtime",
+            )?,
+            global_queue_depth: IntGauge::new(
+                "tokio_global_queue_depth",
+                "Number of tasks waiting in the Tokio runtime's global queue",
+            )?,
+            busy_seconds: Counter::with_opts(Opts::new(
+                "tokio_worker_busy_seconds_total",
+                "Time Tokio worker threads spent busy, summed over all workers",
+            ))?,
+            busy_lock: Mutex::new(()),
+        })
+    }
+}
+
+impl Collector for TokioCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        [
+            self.workers.desc(),
+            self.alive_tasks.desc(),
+            self.global_queue_depth.desc(),
+            self.busy_seconds.desc(),
+        ]
+        .concat()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        // Scrapes outside a runtime (e.g. from a plain thread) have nothing to report
+        let Ok(handle) = Handle::try_current() else {
+            return Vec::new();
+        };
+        let metrics = handle.metrics();
+
+        self.workers.set(metrics.num_workers() as i64);
+        self.alive_tasks.set(metrics.num_alive_tasks() as i64);
+        self.global_queue_depth
+            .set(metrics.global_queue_depth() as i64);
+
+        {
+            let _guard = self.busy_lock.lock().expect("busy lock poisoned");
+            let busy: f64 = (0..metrics.num_workers())
+                .map(|worker| metrics.worker_total_busy_duration(worker).as_secs_f64())
+                .sum();
+            let delta = busy - self.busy_seconds.get();
+            if delta > 0.0 {
+                self.busy_seconds.inc_by(delta);
+            }
+        }
+
+        [
+            self.workers.collect(),
+            self.alive_tasks.collect(),
+            self.global_queue_depth.collect(),
+            self.busy_seconds.collect(),
+        ]
+        .concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_reports_runtime_metrics() {
+        let collector = TokioCollector::new().unwrap();
+        let families = collector.collect();
+
+        let names: Vec<_> = families.iter().map(|family| family.get_name()).collect();
+        assert_eq!(
+            names,
+            [
+                "tokio_workers",
+                "tokio_alive_tasks",
+                "tokio_global_queue_depth",
+                "tokio_worker_busy_seconds_total",
+            ]
+        );
+        assert_eq!(collector.workers.get(), 2);
+    }
+
+    #[test]
+    fn test_empty_outside_runtime() {
+        assert!(TokioCollector::new().unwrap().collect().is_empty());
+    }
+}
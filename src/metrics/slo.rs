@@ -0,0 +1,388 @@
+use crate::config::SloConfig;
+use anyhow::Result;
+use prometheus::core::{Collector, Desc};
+use prometheus::proto::MetricFamily;
+use prometheus::{GaugeVec, Opts, Registry};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use utoipa::ToSchema;
+
+/// Route the SLOs cover
+pub const SLO_ROUTE: &str = "/predict";
+
+/// Burn rate windows as (label, minutes)
+const WINDOWS: [(&str, u64); 7] = [
+    ("5m", 5),
+    ("30m", 30),
+    ("1h", 60),
+    ("2h", 120),
+    ("6h", 360),
+    ("1d", 1_440),
+    ("3d", 4_320),
+];
+
+/// Multi-window burn rate alerts as (name, long window, short window,
+/// threshold); both windows must burn faster than the threshold. These are
+/// the usual page and ticket pairs for a 30-day budget.
+const ALERTS: [(&str, &str, &str, f64); 4] = [
+    ("page_fast", "1h", "5m", 14.4),
+    ("page_slow", "6h", "30m", 6.0),
+    ("ticket_fast", "1d", "2h", 3.0),
+    ("ticket_slow", "3d", "6h", 1.0),
+];
+
+/// Minutes of history kept; the longest window
+const HISTORY_MINUTES: u64 = 4_320;
+
+/// Burn rate of one objective over one window
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SloWindow {
+    pub window: String,
+    /// Requests to the route in the window
+    pub requests: u64,
+    /// Requests that missed the objective
+    pub bad: u64,
+    /// Share of good requests; absent without requests
+    pub sli: Option<f64>,
+    /// Rate the error budget is spent at; 1 spends it exactly over the SLO
+    /// period, 0 without requests
+    pub burn_rate: f64,
+}
+
+/// Multi-window burn rate alert
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SloAlert {
+    pub name: String,
+    pub long_window: String,
+    pub short_window: String,
+    pub burn_rate_threshold: f64,
+    /// Whether both windows burn faster than the threshold
+    pub firing: bool,
+}
+
+/// Burn rates and alerts of one objective
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SloObjective {
+    /// `availability` or `latency`
+    pub name: String,
+    /// Target share of good requests
+    pub target: f64,
+    /// Slowest response counted as good, for the latency objective
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_threshold_ms: Option<u64>,
+    pub windows: Vec<SloWindow>,
+    pub alerts: Vec<SloAlert>,
+}
+
+/// Error budget burn of the configured SLOs
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SloReport {
+    pub route: String,
+    pub objectives: Vec<SloObjective>,
+}
+
+/// Request counts of one minute
+#[derive(Debug, Clone, Copy, Default)]
+struct MinuteCounts {
+    minute: u64,
+    requests: u64,
+    /// 5xx responses, including route timeouts
+    errors: u64,
+    /// Responses slower than the latency threshold
+    slow: u64,
+}
+
+/// Availability and latency SLOs for `SLO_ROUTE`, computed from the requests
+/// passed to `AppMetrics::record_http_request`
+///
+/// Counts are kept per minute for the longest window, so burn rates have
+/// minute resolution.
+pub struct SloTracker {
+    config: SloConfig,
+    start: Instant,
+    minutes: Mutex<Vec<MinuteCounts>>,
+    burn_rate: GaugeVec,
+    alert_firing: GaugeVec,
+}
+
+impl SloTracker {
+    pub fn new(config: SloConfig) -> Result<Self> {
+        Ok(Self {
+            config,
+            start: Instant::now(),
+            minutes: Mutex::new(vec![MinuteCounts::default(); HISTORY_MINUTES as usize]),
+            burn_rate: GaugeVec::new(
+                Opts::new(
+                    "slo_burn_rate",
+                    "Error budget burn rate of the /predict SLOs, by objective and window",
+                ),
+                &["slo", "window"],
+            )?,
+            alert_firing: GaugeVec::new(
+                Opts::new(
+                    "slo_alert_firing",
+                    "1 when both windows of a multi-window burn rate alert exceed its threshold",
+                ),
+                &["slo", "alert"],
+            )?,
+        })
+    }
+
+    /// Export the SLO gauges from `registry`, refreshed on every scrape
+    pub fn register(self: &Arc<Self>, registry: &Registry) -> Result<()> {
+        registry.register(Box::new(SloCollector(self.clone())))?;
+        Ok(())
+    }
+
+    /// Count a finished request; requests to other routes are ignored
+    pub fn record(&self, method: &str, endpoint: &str, status: u16, duration: f64) {
+        if method == "POST" && endpoint == SLO_ROUTE {
+            self.record_at(self.current_minute(), status, duration);
+        }
+    }
+
+    pub fn report(&self) -> SloReport {
+        self.report_at(self.current_minute())
+    }
+
+    fn current_minute(&self) -> u64 {
+        // Offset so the first minutes never alias the zeroed slots
+        self.start.elapsed().as_secs() / 60 + HISTORY_MINUTES
+    }
+
+    fn record_at(&self, minute: u64, status: u16, duration: f64) {
+        let mut minutes = self.minutes.lock().expect("slo lock poisoned");
+        let slot = &mut minutes[(minute % HISTORY_MINUTES) as usize];
+        if slot.minute != minute {
+            *slot = MinuteCounts {
+                minute,
+                ..MinuteCounts::default()
+            };
+        }
+        slot.requests += 1;
+        if status >= 500 {
+            slot.errors += 1;
+        }
+        if duration * 1000.0 > self.config.latency_threshold_ms as f64 {
+            slot.slow += 1;
+        }
+    }
+
+    fn report_at(&self, now: u64) -> SloReport {
+        // (requests, errors, slow) per window
+        let mut totals = [(0u64, 0u64, 0u64); WINDOWS.len()];
+        for counts in self.minutes.lock().expect("slo lock poisoned").iter() {
+            let Some(age) = now.checked_sub(counts.minute) else {
+                continue;
+            };
+            for (total, (_, minutes)) in totals.iter_mut().zip(WINDOWS) {
+                if age < minutes {
+                    total.0 += counts.requests;
+                    total.1 += counts.errors;
+                    total.2 += counts.slow;
+                }
+            }
+        }
+
+        let counts = |bad: fn(&(u64, u64, u64)) -> u64| -> Vec<(u64, u64)> {
+            totals.iter().map(|total| (total.0, bad(total))).collect()
+        };
+        SloReport {
+            route: SLO_ROUTE.to_string(),
+            objectives: vec![
+                objective(
+                    "availability",
+                    self.config.availability_target,
+                    None,
+                    &counts(|total| total.1),
+                ),
+                objective(
+                    "latency",
+                    self.config.latency_target,
+                    Some(self.config.latency_threshold_ms),
+                    &counts(|total| total.2),
+                ),
+            ],
+        }
+    }
+}
+
+/// Burn rates and alert states of one objective from `(requests, bad)`
+/// counts in the order of `WINDOWS`
+fn objective(
+    name: &str,
+    target: f64,
+    latency_threshold_ms: Option<u64>,
+    counts: &[(u64, u64)],
+) -> SloObjective {
+    let windows: Vec<SloWindow> = WINDOWS
+        .iter()
+        .zip(counts)
+        .map(|((window, _), &(requests, bad))| {
+            let error_rate = (requests > 0).then(|| bad as f64 / requests as f64);
+            SloWindow {
+                window: window.to_string(),
+                requests,
+                bad,
+                sli: error_rate.map(|rate| 1.0 - rate),
+                burn_rate: error_rate.unwrap_or(0.0) / (1.0 - target),
+            }
+        })
+        .collect();
+    let burn_rate = |window: &str| {
+        windows
+            .iter()
+            .find(|w| w.window == window)
+            .map_or(0.0, |w| w.burn_rate)
+    };
+    let alerts = ALERTS
+        .iter()
+        .map(|&(alert, long_window, short_window, threshold)| SloAlert {
+            name: alert.to_string(),
+            long_window: long_window.to_string(),
+            short_window: short_window.to_string(),
+            burn_rate_threshold: threshold,
+            firing: burn_rate(long_window) > threshold && burn_rate(short_window) > threshold,
+        })
+        .collect();
+    SloObjective {
+        name: name.to_string(),
+        target,
+        latency_threshold_ms,
+        windows,
+        alerts,
+    }
+}
+
+/// Sets the SLO gauges from a fresh report when scraped
+struct SloCollector(Arc<SloTracker>);
+
+impl Collector for SloCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        [self.0.burn_rate.desc(), self.0.alert_firing.desc()].concat()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let tracker = &self.0;
+        for objective in tracker.report().objectives {
+            for window in &objective.windows {
+                tracker
+                    .burn_rate
+                    .with_label_values(&[objective.name.as_str(), window.window.as_str()])
+                    .set(window.burn_rate);
+            }
+            for alert in &objective.alerts {
+                tracker
+                    .alert_firing
+                    .with_label_values(&[objective.name.as_str(), alert.name.as_str()])
+                    .set(if alert.firing { 1.0 } else { 0.0 });
+            }
+        }
+        [tracker.burn_rate.collect(), tracker.alert_firing.collect()].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> SloTracker {
+        SloTracker::new(SloConfig {
+            availability_target: 0.99,
+            latency_target: 0.9,
+            latency_threshold_ms: 100,
+            ..SloConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn window<'a>(report: &'a SloReport, objective: &str, window: &str) -> &'a SloWindow {
+        report
+            .objectives
+            .iter()
+            .find(|o| o.name == objective)
+            .unwrap()
+            .windows
+            .iter()
+            .find(|w| w.window == window)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_burn_rates_per_window() {
+        let tracker = tracker();
+        let now = HISTORY_MINUTES + 1_000;
+        // Two hours ago: healthy traffic
+        for _ in 0..100 {
+            tracker.record_at(now - 120, 200, 0.01);
+        }
+        // Last minute: 2 errors and 5 slow responses out of 10
+        for i in 0..10 {
+            let status = if i < 2 { 503 } else { 200 };
+            let duration = if i < 5 { 0.5 } else { 0.01 };
+            tracker.record_at(now, status, duration);
+        }
+
+        let report = tracker.report_at(now);
+        let recent = window(&report, "availability", "5m");
+        assert_eq!((recent.requests, recent.bad), (10, 2));
+        assert!((recent.burn_rate - 20.0).abs() < 1e-9);
+        assert!((recent.sli.unwrap() - 0.8).abs() < 1e-9);
+
+        let longer = window(&report, "availability", "6h");
+        assert_eq!((longer.requests, longer.bad), (110, 2));
+
+        let latency = window(&report, "latency", "5m");
+        assert_eq!(latency.bad, 5);
+        assert!((latency.burn_rate - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_alert_needs_both_windows() {
+        let tracker = tracker();
+        let now = HISTORY_MINUTES + 1_000;
+        // An error burst that has already stopped
+        for _ in 0..50 {
+            tracker.record_at(now - 30, 500, 0.01);
+        }
+        tracker.record_at(now, 200, 0.01);
+
+        let report = tracker.report_at(now);
+        let availability = &report.objectives[0];
+        let page_fast = &availability.alerts[0];
+        assert_eq!(page_fast.name, "page_fast");
+        // 1h burns fast, but the last 5 minutes are clean
+        assert!(window(&report, "availability", "1h").burn_rate > 14.4);
+        assert!(!page_fast.firing);
+
+        tracker.record_at(now, 500, 0.01);
+        let report = tracker.report_at(now);
+        assert!(report.objectives[0].alerts[0].firing);
+    }
+
+    #[test]
+    fn test_only_server_errors_are_bad() {
+        let tracker = tracker();
+        let now = HISTORY_MINUTES + 1_000;
+        for status in [200, 400, 408, 429, 500, 504] {
+            tracker.record_at(now, status, 0.01);
+        }
+
+        let report = tracker.report_at(now);
+        assert_eq!(window(&report, "availability", "5m").bad, 2);
+    }
+
+    #[test]
+    fn test_old_minutes_expire_and_other_routes_ignored() {
+        let tracker = tracker();
+        let now = HISTORY_MINUTES + 1_000;
+        tracker.record_at(now - HISTORY_MINUTES, 500, 0.01);
+        tracker.record("GET", "/health", 500, 1.0);
+
+        let report = tracker.report_at(now);
+        assert_eq!(window(&report, "availability", "3d").requests, 0);
+        assert_eq!(window(&report, "availability", "3d").burn_rate, 0.0);
+        assert_eq!(window(&report, "availability", "3d").sli, None);
+    }
+}
@@ -1,21 +1,133 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
 use ndarray::Array1;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
 use std::sync::OnceLock;
+use utoipa::ToSchema;
 
 const EXPECTED_FEATURES: usize = 10;
 const MODEL_VERSION: &str = "v1.0.0";
+const MODEL_NAME: &str = "linear-regression";
+const MODEL_TYPE: &str = "linear_regression";
+const OUTPUT_TYPE: &str = "continuous";
+const DEFAULT_WEIGHTS: [f64; EXPECTED_FEATURES] = [
+    0.15, -0.23, 0.87, -0.45, 0.67, 0.34, -0.12, 0.89, -0.56, 0.78,
+];
+const DEFAULT_BIAS: f64 = 2.5;
+const MIN_CONFIDENCE: f64 = 0.85;
+const MAX_CONFIDENCE: f64 = 1.0;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PredictionRequest {
     pub features: Vec<f64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PredictionResponse {
     pub prediction: f64,
     pub confidence: f64,
     pub model_version: String,
+    /// Correlates the prediction with server logs; set to the `X-Request-Id`
+    /// response header when served over HTTP
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prediction_id: Option<String>,
+    /// Largest absolute z-score of any feature against the training data;
+    /// absent when the model has no reference profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ood_score: Option<f64>,
+}
+
+/// Model metadata and input/output schema exposed to clients
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ModelMetadata {
+    pub name: String,
+    pub version: String,
+    pub model_type: String,
+    pub feature_names: Vec<String>,
+    pub feature_count: usize,
+    pub output_type: String,
+    pub loaded_at: DateTime<Utc>,
+    pub checksum: String,
+}
+
+/// Model artifact as stored on disk (JSON)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelArtifact {
+    pub version: String,
+    pub weights: Vec<f64>,
+    pub bias: f64,
+    #[serde(default)]
+    pub feature_names: Vec<String>,
+    /// Training-data input distribution, used to detect drift
+    #[serde(default)]
+    pub reference_profile: Option<ReferenceProfile>,
+}
+
+/// Distribution of each input feature in the training data, in feature order
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReferenceProfile {
+    pub features: Vec<FeatureProfile>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeatureProfile {
+    pub mean: f64,
+    pub std_dev: f64,
+    /// Strictly increasing inner bin edges; `n` edges make `n + 1` bins, the
+    /// first `(-inf, edges[0]]` and the last `(edges[n - 1], inf)`
+    pub bin_edges: Vec<f64>,
+    /// Share of training samples in each bin
+    pub bin_proportions: Vec<f64>,
+}
+
+impl ReferenceProfile {
+    fn validate(&self, feature_count: usize) -> Result<()> {
+        if self.features.len() != feature_count {
+            return Err(anyhow!(
+                "reference profile describes {} features but the model has {}",
+                self.features.len(),
+                feature_count
+            ));
+        }
+        for (i, feature) in self.features.iter().enumerate() {
+            if !feature.mean.is_finite() || !feature.std_dev.is_finite() || feature.std_dev < 0.0 {
+                return Err(anyhow!(
+                    "reference profile feature {} has an invalid mean or std_dev",
+                    i
+                ));
+            }
+            if feature.bin_edges.iter().any(|edge| !edge.is_finite())
+                || feature.bin_edges.windows(2).any(|pair| pair[0] >= pair[1])
+            {
+                return Err(anyhow!(
+                    "reference profile feature {} bin_edges must be finite and strictly increasing",
+                    i
+                ));
+            }
+            if feature.bin_proportions.len() != feature.bin_edges.len() + 1 {
+                return Err(anyhow!(
+                    "reference profile feature {} needs one more bin proportion than bin edges",
+                    i
+                ));
+            }
+            if feature
+                .bin_proportions
+                .iter()
+                .any(|share| !share.is_finite() || *share < 0.0)
+            {
+                return Err(anyhow!(
+                    "reference profile feature {} has a negative or non-finite bin proportion",
+                    i
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Mock ML Model implementing simple linear regression
@@ -23,6 +135,11 @@ pub struct PredictionResponse {
 pub struct LinearRegressionModel {
     weights: Array1<f64>,
     bias: f64,
+    version: String,
+    feature_names: Vec<String>,
+    reference_profile: Option<ReferenceProfile>,
+    checksum: String,
+    loaded_at: DateTime<Utc>,
 }
 
 impl Default for LinearRegressionModel {
@@ -35,20 +152,119 @@ impl LinearRegressionModel {
     /// Create a new linear regression model with random weights
     pub fn new() -> Self {
         // Initialize with deterministic "random" weights for consistent results
-        let weights = Array1::from_vec(vec![
-            0.15, -0.23, 0.87, -0.45, 0.67, 0.34, -0.12, 0.89, -0.56, 0.78,
-        ]);
-        let bias = 2.5;
+        let artifact = ModelArtifact {
+            version: MODEL_VERSION.to_string(),
+            weights: DEFAULT_WEIGHTS.to_vec(),
+            bias: DEFAULT_BIAS,
+            feature_names: Vec::new(),
+            reference_profile: None,
+        };
+
+        // SHA-256 over the built-in parameters (weights then bias, little-endian)
+        let mut hasher = Sha256::new();
+        for weight in &artifact.weights {
+            hasher.update(weight.to_le_bytes());
+        }
+        hasher.update(artifact.bias.to_le_bytes());
+        let checksum = format!("sha256:{:x}", hasher.finalize());
+
+        Self::from_artifact(artifact, checksum)
+    }
+
+    /// Load a model from a JSON artifact on disk
+    ///
+    /// The checksum is the SHA-256 of the artifact file contents.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read model artifact {}", path.display()))?;
+        let artifact: ModelArtifact = serde_json::from_slice(&bytes)
+            .with_context(|| format!("Invalid model artifact {}", path.display()))?;
+
+        if artifact.weights.is_empty() {
+            return Err(anyhow!("Model artifact {} has no weights", path.display()));
+        }
+        if !artifact.bias.is_finite() || artifact.weights.iter().any(|w| !w.is_finite()) {
+            return Err(anyhow!(
+                "Model artifact {} contains non-finite parameters",
+                path.display()
+            ));
+        }
+        if !artifact.feature_names.is_empty()
+            && artifact.feature_names.len() != artifact.weights.len()
+        {
+            return Err(anyhow!(
+                "Model artifact {} names {} features but has {} weights",
+                path.display(),
+                artifact.feature_names.len(),
+                artifact.weights.len()
+            ));
+        }
+
+        if let Some(profile) = &artifact.reference_profile {
+            profile
+                .validate(artifact.weights.len())
+                .with_context(|| format!("Invalid model artifact {}", path.display()))?;
+        }
+
+        let checksum = format!("sha256:{:x}", Sha256::digest(&bytes));
+        Ok(Self::from_artifact(artifact, checksum))
+    }
+
+    fn from_artifact(artifact: ModelArtifact, checksum: String) -> Self {
+        let feature_names = if artifact.feature_names.is_empty() {
+            (0..artifact.weights.len())
+                .map(|i| format!("feature_{}", i))
+                .collect()
+        } else {
+            artifact.feature_names
+        };
+
+        Self {
+            weights: Array1::from_vec(artifact.weights),
+            bias: artifact.bias,
+            version: artifact.version,
+            feature_names,
+            reference_profile: artifact.reference_profile,
+            checksum,
+            loaded_at: Utc::now(),
+        }
+    }
+
+    /// Model version reported with every prediction
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Input feature names, in the order `predict` expects them
+    pub fn feature_names(&self) -> &[String] {
+        &self.feature_names
+    }
 
-        Self { weights, bias }
+    /// Training-data input distribution, if the artifact included one
+    pub fn reference_profile(&self) -> Option<&ReferenceProfile> {
+        self.reference_profile.as_ref()
+    }
+
+    /// Describe the model: version, type, input schema and artifact checksum
+    pub fn metadata(&self) -> ModelMetadata {
+        ModelMetadata {
+            name: MODEL_NAME.to_string(),
+            version: self.version.clone(),
+            model_type: MODEL_TYPE.to_string(),
+            feature_names: self.feature_names.clone(),
+            feature_count: self.weights.len(),
+            output_type: OUTPUT_TYPE.to_string(),
+            loaded_at: self.loaded_at,
+            checksum: self.checksum.clone(),
+        }
     }
 
     /// Validate input features
     fn validate_features(&self, features: &[f64]) -> Result<()> {
-        if features.len() != EXPECTED_FEATURES {
+        if features.len() != self.weights.len() {
             return Err(anyhow!(
                 "Expected {} features, got {}",
-                EXPECTED_FEATURES,
+                self.weights.len(),
                 features.len()
             ));
         }
@@ -63,6 +279,28 @@ impl LinearRegressionModel {
         Ok(())
     }
 
+    /// Largest absolute z-score of `features` against the training means and
+    /// standard deviations, or `None` without a reference profile
+    ///
+    /// Features that did not vary in the training data are skipped.
+    pub fn ood_score(&self, features: &[f64]) -> Option<f64> {
+        let profile = self.reference_profile.as_ref()?;
+        Some(
+            profile
+                .features
+                .iter()
+                .zip(features)
+                .filter(|(feature, _)| feature.std_dev > 0.0)
+                .map(|(feature, value)| ((value - feature.mean) / feature.std_dev).abs())
+                .fold(0.0, f64::max),
+        )
+    }
+
+    /// Lowest and highest confidence this model type can report
+    pub fn confidence_range() -> (f64, f64) {
+        (MIN_CONFIDENCE, MAX_CONFIDENCE)
+    }
+
     /// Generate confidence score based on prediction magnitude
     fn calculate_confidence(&self, prediction: f64) -> f64 {
         // Simple confidence calculation: higher for predictions closer to bias
@@ -70,20 +308,23 @@ impl LinearRegressionModel {
         let max_distance = 50.0; // Reasonable max distance for normalization
         let normalized_distance = (distance_from_bias / max_distance).min(1.0);
 
-        // Confidence between 0.85 and 1.0
-        0.85 + (1.0 - normalized_distance) * 0.15
+        MIN_CONFIDENCE + (1.0 - normalized_distance) * (MAX_CONFIDENCE - MIN_CONFIDENCE)
     }
 
     /// Perform prediction using linear regression
     pub fn predict(&self, features: &[f64]) -> Result<PredictionResponse> {
         // Validate input
-        self.validate_features(features)?;
+        tracing::info_span!("validate_features", feature_count = features.len())
+            .in_scope(|| self.validate_features(features))?;
 
-        // Convert to ndarray for efficient computation
-        let feature_array = Array1::from_vec(features.to_vec());
+        let prediction = tracing::info_span!("model_inference", model_version = %self.version)
+            .in_scope(|| {
+                // Convert to ndarray for efficient computation
+                let feature_array = Array1::from_vec(features.to_vec());
 
-        // Linear regression: prediction = weights * features + bias
-        let prediction = self.weights.dot(&feature_array) + self.bias;
+                // Linear regression: prediction = weights * features + bias
+                self.weights.dot(&feature_array) + self.bias
+            });
 
         // Calculate confidence score
         let confidence = self.calculate_confidence(prediction);
@@ -97,17 +338,47 @@ impl LinearRegressionModel {
         Ok(PredictionResponse {
             prediction,
             confidence,
-            model_version: MODEL_VERSION.to_string(),
+            model_version: self.version.clone(),
+            prediction_id: None,
+            ood_score: self.ood_score(features),
         })
     }
 }
 
-/// Global model instance using OnceLock for thread-safe lazy initialization
+/// Global model instance, set once by `load_model`
 static MODEL_INSTANCE: OnceLock<LinearRegressionModel> = OnceLock::new();
 
-/// Get the global model instance
-pub fn get_model() -> &'static LinearRegressionModel {
-    MODEL_INSTANCE.get_or_init(LinearRegressionModel::new)
+/// Get the global model instance; `None` until `load_model` has finished
+///
+/// Never initializes the model, so a request arriving while the configured
+/// artifact is still loading cannot lock in the built-in model.
+pub fn get_model() -> Option<&'static LinearRegressionModel> {
+    MODEL_INSTANCE.get()
+}
+
+/// Initialize the global model, from an artifact file if one is configured
+///
+/// Without a path the built-in model is used, and loading it again is a
+/// no-op. Loading an artifact fails if the global model was already
+/// initialized.
+pub fn load_model(path: Option<&Path>) -> Result<&'static LinearRegressionModel> {
+    let Some(path) = path else {
+        return Ok(MODEL_INSTANCE.get_or_init(LinearRegressionModel::new));
+    };
+
+    let model = LinearRegressionModel::from_file(path)?;
+    MODEL_INSTANCE
+        .set(model)
+        .map_err(|_| anyhow!("Model was already initialized"))?;
+
+    Ok(MODEL_INSTANCE.get().expect("model set above"))
+}
+
+/// Run a prediction on the loaded global model so it is warm before serving
+pub fn warm_up() -> Result<()> {
+    let model = get_model().ok_or_else(|| anyhow!("Model is not loaded"))?;
+    model.predict(&vec![0.0; model.weights.len()])?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -118,7 +389,7 @@ mod tests {
     fn test_model_creation() {
         let model = LinearRegressionModel::new();
         assert_eq!(model.weights.len(), EXPECTED_FEATURES);
-        assert_eq!(model.bias, 2.5);
+        assert_eq!(model.bias, DEFAULT_BIAS);
     }
 
     #[test]
@@ -129,8 +400,11 @@ mod tests {
 
         assert!(result.is_ok());
         let response = result.unwrap();
-        assert!(response.confidence >= 0.85 && response.confidence <= 1.0);
+        let (min, max) = LinearRegressionModel::confidence_range();
+        assert!(response.confidence >= min && response.confidence <= max);
         assert_eq!(response.model_version, MODEL_VERSION);
+        // The HTTP handler assigns the request ID
+        assert_eq!(response.prediction_id, None);
     }
 
     #[test]
@@ -152,10 +426,84 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_model_metadata() {
+        let model = LinearRegressionModel::new();
+        let metadata = model.metadata();
+
+        assert_eq!(metadata.name, MODEL_NAME);
+        assert_eq!(metadata.version, MODEL_VERSION);
+        assert_eq!(metadata.feature_count, EXPECTED_FEATURES);
+        assert_eq!(metadata.feature_names.len(), EXPECTED_FEATURES);
+        assert!(metadata.checksum.starts_with("sha256:"));
+        // Checksum depends only on parameters, not on load time
+        assert_eq!(metadata.checksum, LinearRegressionModel::new().checksum);
+    }
+
+    #[test]
+    fn test_model_from_file() {
+        let path = std::env::temp_dir().join(format!("model-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            r#"{"version": "v2.0.0", "weights": [1.0, 2.0], "bias": 0.5, "feature_names": ["a", "b"]}"#,
+        )
+        .unwrap();
+
+        let model = LinearRegressionModel::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let response = model.predict(&[1.0, 1.0]).unwrap();
+        assert_eq!(response.prediction, 3.5);
+        assert_eq!(response.model_version, "v2.0.0");
+        assert_eq!(model.metadata().feature_names, vec!["a", "b"]);
+        assert!(model.predict(&[1.0; EXPECTED_FEATURES]).is_err());
+    }
+
+    #[test]
+    fn test_model_from_invalid_file() {
+        let path = std::env::temp_dir().join(format!("model-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            r#"{"version": "v2.0.0", "weights": [1.0, 2.0], "bias": 0.5, "feature_names": ["a"]}"#,
+        )
+        .unwrap();
+
+        let result = LinearRegressionModel::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_model_reference_profile() {
+        let path = std::env::temp_dir().join(format!("model-{}.json", uuid::Uuid::new_v4()));
+        let artifact = |proportions: &str| {
+            format!(
+                r#"{{"version": "v2.0.0", "weights": [1.0], "bias": 0.0,
+                    "reference_profile": {{"features": [
+                        {{"mean": 0.0, "std_dev": 1.0, "bin_edges": [-1.0, 1.0], "bin_proportions": {}}}
+                    ]}}}}"#,
+                proportions
+            )
+        };
+
+        std::fs::write(&path, artifact("[0.16, 0.68, 0.16]")).unwrap();
+        let model = LinearRegressionModel::from_file(&path).unwrap();
+        assert_eq!(model.reference_profile().unwrap().features[0].std_dev, 1.0);
+        assert_eq!(model.predict(&[-3.0]).unwrap().ood_score, Some(3.0));
+        assert_eq!(LinearRegressionModel::new().ood_score(&[0.0; 10]), None);
+
+        // Proportions must cover every bin
+        std::fs::write(&path, artifact("[0.5, 0.5]")).unwrap();
+        let result = LinearRegressionModel::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_global_model_instance() {
-        let model1 = get_model();
-        let model2 = get_model();
+        let model1 = load_model(None).unwrap();
+        let model2 = get_model().unwrap();
 
         // Should be the same instance
         assert_eq!(std::ptr::addr_of!(*model1), std::ptr::addr_of!(*model2));
@@ -0,0 +1,33 @@
+use crate::handlers::{admin, health, models, predict, slo};
+use crate::metrics::prometheus;
+use utoipa::OpenApi;
+
+/// OpenAPI 3 document generated from the handler annotations and schema types
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "AI Model Service",
+        description = "Rust web service for AI model inference"
+    ),
+    paths(
+        health::health_check,
+        health::liveness,
+        health::readiness,
+        predict::predict,
+        models::list_models,
+        models::get_model_metadata,
+        models::get_model_drift,
+        prometheus::metrics_handler,
+        slo::get_slo,
+        admin::get_log_level,
+        admin::set_log_level,
+    ),
+    tags(
+        (name = "health", description = "Service health"),
+        (name = "inference", description = "Model predictions"),
+        (name = "models", description = "Model metadata and input schema"),
+        (name = "observability", description = "Metrics and SLOs"),
+        (name = "admin", description = "Runtime administration; requires the admin role")
+    )
+)]
+pub struct ApiDoc;
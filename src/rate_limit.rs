@@ -0,0 +1,332 @@
+use crate::auth::Principal;
+use crate::config::{RateLimitConfig, RateLimitTierConfig};
+use crate::metrics::prometheus::AppMetrics;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Most clients tracked at once; beyond it the least recently used bucket is evicted
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+static RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+static RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+static RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// Position in `Buckets::recency`
+    used: u64,
+}
+
+impl Bucket {
+    fn refill(&mut self, tier: &RateLimitTierConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * tier.refill_per_sec).min(tier.burst as f64);
+        self.updated = now;
+    }
+}
+
+/// Buckets by client, with an index from last use to client for LRU eviction
+#[derive(Default)]
+struct Buckets {
+    by_client: HashMap<String, Bucket>,
+    recency: BTreeMap<u64, String>,
+    next_use: u64,
+}
+
+impl Buckets {
+    /// The bucket for `key`, marked as most recently used; creating it evicts
+    /// the least recently used bucket when `capacity` clients are tracked
+    fn touch(&mut self, key: &str, capacity: usize, new: impl FnOnce() -> Bucket) -> &mut Bucket {
+        let used = self.next_use;
+        self.next_use += 1;
+
+        if let Some(bucket) = self.by_client.get_mut(key) {
+            self.recency.remove(&bucket.used);
+            self.recency.insert(used, key.to_string());
+            bucket.used = used;
+            return self.by_client.get_mut(key).expect("bucket present above");
+        }
+
+        if self.by_client.len() >= capacity {
+            if let Some((_, evicted)) = self.recency.pop_first() {
+                self.by_client.remove(&evicted);
+            }
+        }
+        self.recency.insert(used, key.to_string());
+        self.by_client
+            .entry(key.to_string())
+            .or_insert_with(|| Bucket { used, ..new() })
+    }
+}
+
+/// Result of taking a token from a client's bucket
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Time until the bucket is full again
+    pub reset: Duration,
+    /// Time until the next request would be allowed (zero when allowed)
+    pub retry_after: Duration,
+}
+
+/// Token-bucket rate limiter keyed by API credential or client IP
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<Buckets>,
+    max_clients: usize,
+    metrics: Arc<AppMetrics>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig, metrics: Arc<AppMetrics>) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(Buckets::default()),
+            max_clients: MAX_TRACKED_CLIENTS,
+            metrics,
+        }
+    }
+
+    /// Take one token for `key` in `tier`, refilling based on elapsed time
+    pub fn check(&self, tier_name: &str, key: &str, now: Instant) -> Decision {
+        let Some(tier) = self.config.tiers.get(tier_name) else {
+            // Unknown tiers are rejected by config validation; fail open if one slips through
+            tracing::warn!(tier = %tier_name, "Unknown rate limit tier");
+            return Decision {
+                allowed: true,
+                limit: 0,
+                remaining: 0,
+                reset: Duration::ZERO,
+                retry_after: Duration::ZERO,
+            };
+        };
+
+        let mut buckets = self.buckets.lock().expect("rate limiter lock poisoned");
+        let bucket = buckets.touch(key, self.max_clients, || Bucket {
+            tokens: tier.burst as f64,
+            updated: now,
+            used: 0,
+        });
+        bucket.refill(tier, now);
+
+        let allowed = bucket.tokens >= 1.0;
+        let retry_after = if allowed {
+            bucket.tokens -= 1.0;
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - bucket.tokens) / tier.refill_per_sec)
+        };
+
+        Decision {
+            allowed,
+            limit: tier.burst,
+            remaining: bucket.tokens.floor() as u32,
+            reset: Duration::from_secs_f64(
+                (tier.burst as f64 - bucket.tokens) / tier.refill_per_sec,
+            ),
+            retry_after,
+        }
+    }
+
+    /// Identify the caller: the authenticated subject, or else the client IP
+    fn client(&self, request: &Request) -> (String, String) {
+        if let Some(principal) = request.extensions().get::<Principal>() {
+            let tier = principal
+                .tier
+                .clone()
+                .unwrap_or_else(|| self.config.authenticated_tier.clone());
+            return (tier, format!("subject:{}", principal.subject));
+        }
+
+        let forwarded = self
+            .config
+            .trust_forwarded_for
+            .then(|| forwarded_for(request.headers()))
+            .flatten();
+        let ip = forwarded
+            .or_else(|| {
+                request
+                    .extensions()
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|ConnectInfo(address)| address.ip().to_string())
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+
+        (self.config.anonymous_tier.clone(), format!("ip:{}", ip))
+    }
+}
+
+/// The last `X-Forwarded-For` address: the one appended by the trusted proxy.
+/// Earlier entries come from the client and can be forged.
+fn forwarded_for(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .next_back()?
+        .to_str()
+        .ok()?
+        .rsplit(',')
+        .next()
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| !ip.is_empty())
+}
+
+fn insert_headers(headers: &mut HeaderMap, decision: &Decision) {
+    let seconds = |d: Duration| HeaderValue::from(d.as_secs_f64().ceil() as u64);
+
+    headers.insert(RATELIMIT_LIMIT.clone(), HeaderValue::from(decision.limit));
+    headers.insert(
+        RATELIMIT_REMAINING.clone(),
+        HeaderValue::from(decision.remaining),
+    );
+    headers.insert(RATELIMIT_RESET.clone(), seconds(decision.reset));
+    if !decision.allowed {
+        headers.insert(
+            axum::http::header::RETRY_AFTER,
+            seconds(decision.retry_after),
+        );
+    }
+}
+
+/// Rate limiting middleware; rejects with 429 and `Retry-After` when a bucket is empty
+pub async fn rate_limit_middleware(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (tier, key) = limiter.client(&request);
+    let decision = limiter.check(&tier, &key, Instant::now());
+
+    if !decision.allowed {
+        limiter.metrics.record_rate_limited(&tier);
+        tracing::info!(tier = %tier, client = %key, "Request rate limited");
+
+        let mut response = (StatusCode::TOO_MANY_REQUESTS, "rate limit exceeded").into_response();
+        insert_headers(response.headers_mut(), &decision);
+        return response;
+    }
+
+    let mut response = next.run(request).await;
+    insert_headers(response.headers_mut(), &decision);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter() -> RateLimiter {
+        let mut config = RateLimitConfig::default();
+        config.tiers.insert(
+            "test".to_string(),
+            RateLimitTierConfig {
+                burst: 2,
+                refill_per_sec: 1.0,
+            },
+        );
+        RateLimiter::new(config, Arc::new(AppMetrics::new().unwrap()))
+    }
+
+    #[test]
+    fn test_burst_then_reject() {
+        let limiter = limiter();
+        let now = Instant::now();
+
+        assert!(limiter.check("test", "a", now).allowed);
+        let decision = limiter.check("test", "a", now);
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 0);
+
+        let decision = limiter.check("test", "a", now);
+        assert!(!decision.allowed);
+        assert_eq!(decision.retry_after, Duration::from_secs(1));
+
+        // Buckets are per client
+        assert!(limiter.check("test", "b", now).allowed);
+    }
+
+    #[test]
+    fn test_refill_over_time() {
+        let limiter = limiter();
+        let now = Instant::now();
+
+        limiter.check("test", "a", now);
+        limiter.check("test", "a", now);
+        assert!(!limiter.check("test", "a", now).allowed);
+
+        let later = now + Duration::from_millis(1500);
+        let decision = limiter.check("test", "a", later);
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 0);
+    }
+
+    #[test]
+    fn test_tracked_clients_are_capped() {
+        let mut limiter = limiter();
+        limiter.max_clients = 3;
+        let now = Instant::now();
+
+        // Drain "a" so an eviction would show up as a full bucket
+        limiter.check("test", "a", now);
+        limiter.check("test", "a", now);
+        limiter.check("test", "b", now);
+        limiter.check("test", "c", now);
+        // "a" is used again, so "b" is the least recently used
+        assert!(!limiter.check("test", "a", now).allowed);
+        limiter.check("test", "d", now);
+
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.by_client.len(), 3);
+        assert_eq!(buckets.recency.len(), 3);
+        assert!(!buckets.by_client.contains_key("b"));
+        assert!(buckets.by_client.contains_key("a"));
+    }
+
+    #[test]
+    fn test_forwarded_for_last_address() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("203.0.113.7, 10.0.0.1"),
+        );
+        assert_eq!(forwarded_for(&headers).as_deref(), Some("10.0.0.1"));
+
+        headers.append("x-forwarded-for", HeaderValue::from_static("198.51.100.2"));
+        assert_eq!(forwarded_for(&headers).as_deref(), Some("198.51.100.2"));
+    }
+
+    #[test]
+    fn test_forged_forwarded_for_does_not_bypass_limit() {
+        let mut config = limiter().config;
+        config.anonymous_tier = "test".to_string();
+        config.trust_forwarded_for = true;
+        let limiter = RateLimiter::new(config, Arc::new(AppMetrics::new().unwrap()));
+        let now = Instant::now();
+
+        // Each request forges a different leftmost address; the proxy appends
+        // the real one
+        let decisions: Vec<bool> = (0..3)
+            .map(|i| {
+                let request = Request::builder()
+                    .header("x-forwarded-for", format!("192.0.2.{}, 203.0.113.7", i))
+                    .body(axum::body::Body::empty())
+                    .unwrap();
+                let (tier, key) = limiter.client(&request);
+                assert_eq!(key, "ip:203.0.113.7");
+                limiter.check(&tier, &key, now).allowed
+            })
+            .collect();
+        assert_eq!(decisions, [true, true, false]);
+    }
+}
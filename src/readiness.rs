@@ -0,0 +1,219 @@
+use crate::config::ReadinessConfig;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+/// Boxed future returned by dependency checks
+pub type CheckFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// An external dependency that must be reachable for the service to be ready
+pub trait DependencyCheck: Send + Sync {
+    /// Name reported in the readiness response
+    fn name(&self) -> &str;
+
+    /// Probe the dependency, returning an error describing the failure
+    fn check(&self) -> CheckFuture<'_>;
+}
+
+/// Checks that a TCP connection can be established to a host and port
+pub struct TcpCheck {
+    name: String,
+    address: String,
+    timeout: Duration,
+}
+
+impl TcpCheck {
+    pub fn new(name: impl Into<String>, address: impl Into<String>, timeout: Duration) -> Self {
+        Self {
+            name: name.into(),
+            address: address.into(),
+            timeout,
+        }
+    }
+}
+
+impl DependencyCheck for TcpCheck {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn check(&self) -> CheckFuture<'_> {
+        Box::pin(async move {
+            match tokio::time::timeout(self.timeout, tokio::net::TcpStream::connect(&self.address))
+                .await
+            {
+                Ok(Ok(_)) => Ok(()),
+                Ok(Err(e)) => Err(anyhow!("connect to {} failed: {}", self.address, e)),
+                Err(_) => Err(anyhow!(
+                    "connect to {} timed out after {:?}",
+                    self.address,
+                    self.timeout
+                )),
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+}
+
+/// Outcome of a single readiness check
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Pass,
+            detail: None,
+        }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Fail,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+/// Tracks whether the service should receive traffic
+///
+/// The service is ready once the model has been loaded and warmed, stays
+/// ready until shutdown draining begins, and is only ready while every
+/// configured dependency check passes.
+pub struct Readiness {
+    model_ready: AtomicBool,
+    draining: AtomicBool,
+    dependencies: Vec<Arc<dyn DependencyCheck>>,
+}
+
+impl Readiness {
+    pub fn new(dependencies: Vec<Arc<dyn DependencyCheck>>) -> Self {
+        Self {
+            model_ready: AtomicBool::new(false),
+            draining: AtomicBool::new(false),
+            dependencies,
+        }
+    }
+
+    /// Build readiness tracking with the dependency checks from configuration
+    pub fn from_config(config: &ReadinessConfig) -> Self {
+        let dependencies = config
+            .dependencies
+            .iter()
+            .map(|dependency| {
+                Arc::new(TcpCheck::new(
+                    dependency.name.clone(),
+                    dependency.address.clone(),
+                    Duration::from_millis(dependency.timeout_ms),
+                )) as Arc<dyn DependencyCheck>
+            })
+            .collect();
+
+        Self::new(dependencies)
+    }
+
+    /// Mark the model as loaded and warmed
+    pub fn set_model_ready(&self) {
+        self.model_ready.store(true, Ordering::SeqCst);
+    }
+
+    /// Start failing readiness so load balancers stop routing new traffic
+    pub fn set_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Run every readiness check and return the individual results
+    pub async fn check(&self) -> Vec<CheckResult> {
+        let mut results = Vec::with_capacity(self.dependencies.len() + 2);
+
+        if self.model_ready.load(Ordering::SeqCst) {
+            results.push(CheckResult::pass("model"));
+        } else {
+            results.push(CheckResult::fail("model", "model is not loaded and warmed"));
+        }
+
+        if self.is_draining() {
+            results.push(CheckResult::fail("shutdown", "service is draining"));
+        } else {
+            results.push(CheckResult::pass("shutdown"));
+        }
+
+        for dependency in &self.dependencies {
+            let result = match dependency.check().await {
+                Ok(()) => CheckResult::pass(dependency.name()),
+                Err(e) => CheckResult::fail(dependency.name(), e.to_string()),
+            };
+            results.push(result);
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_pass(results: &[CheckResult]) -> bool {
+        results.iter().all(|r| r.status == CheckStatus::Pass)
+    }
+
+    #[tokio::test]
+    async fn test_not_ready_until_model_warmed() {
+        let readiness = Readiness::new(Vec::new());
+        assert!(!all_pass(&readiness.check().await));
+
+        readiness.set_model_ready();
+        assert!(all_pass(&readiness.check().await));
+    }
+
+    #[tokio::test]
+    async fn test_not_ready_while_draining() {
+        let readiness = Readiness::new(Vec::new());
+        readiness.set_model_ready();
+        readiness.set_draining();
+
+        let results = readiness.check().await;
+        let shutdown = results.iter().find(|r| r.name == "shutdown").unwrap();
+        assert_eq!(shutdown.status, CheckStatus::Fail);
+    }
+
+    #[tokio::test]
+    async fn test_failing_dependency_check() {
+        // Bind then drop a listener so the port is known to be closed
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let check = TcpCheck::new("database", address, Duration::from_millis(200));
+        let readiness = Readiness::new(vec![Arc::new(check)]);
+        readiness.set_model_ready();
+
+        let results = readiness.check().await;
+        let database = results.iter().find(|r| r.name == "database").unwrap();
+        assert_eq!(database.status, CheckStatus::Fail);
+        assert!(database.detail.is_some());
+    }
+}
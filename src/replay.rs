@@ -0,0 +1,473 @@
+use crate::models::ml_model::{LinearRegressionModel, PredictionRequest};
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Maximum mismatching records listed in the report
+const MAX_REPORTED_MISMATCHES: usize = 20;
+
+/// Replay recorded predictions and compare the outputs
+#[derive(Debug, Clone, Args)]
+pub struct ReplayArgs {
+    /// JSONL file of `PredictionRequest`s or prediction audit log records
+    pub input: PathBuf,
+
+    /// Replay against a running service at this base URL instead of in-process
+    #[arg(long, conflicts_with = "model")]
+    pub url: Option<String>,
+
+    /// Model artifact to replay against in-process; defaults to the built-in model
+    #[arg(long, value_name = "PATH")]
+    pub model: Option<PathBuf>,
+
+    /// API key sent as `X-API-Key` when replaying against a URL
+    #[arg(long, env = "AI_MODEL_SERVICE_API_KEY")]
+    pub api_key: Option<String>,
+
+    /// Largest absolute difference still counted as the same prediction
+    #[arg(long, default_value_t = 1e-9)]
+    pub tolerance: f64,
+
+    /// Replay at most this many records
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// A recorded line: a bare request, or an audit record with its outcome
+#[derive(Debug, Deserialize)]
+struct RecordedPrediction {
+    #[serde(default)]
+    request_id: Option<String>,
+    features: Vec<f64>,
+    #[serde(default)]
+    model_version: Option<String>,
+    #[serde(default)]
+    prediction: Option<f64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl RecordedPrediction {
+    /// Whether the line records an outcome to compare against
+    fn has_outcome(&self) -> bool {
+        self.prediction.is_some() || self.error.is_some()
+    }
+}
+
+/// Result of replaying one record
+#[derive(Debug)]
+struct ReplayOutcome {
+    prediction: Result<f64, String>,
+    model_version: Option<String>,
+    latency: Duration,
+}
+
+/// Latency distribution of a set of requests
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut millis: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        millis.sort_by(f64::total_cmp);
+
+        Self {
+            count: millis.len(),
+            mean_ms: millis.iter().sum::<f64>() / millis.len() as f64,
+            p50_ms: percentile(&millis, 0.50),
+            p90_ms: percentile(&millis, 0.90),
+            p95_ms: percentile(&millis, 0.95),
+            p99_ms: percentile(&millis, 0.99),
+            max_ms: millis[millis.len() - 1],
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+pub fn percentile(sorted: &[f64], quantile: f64) -> f64 {
+    let rank = (quantile * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[derive(Debug, Serialize)]
+pub struct Mismatch {
+    pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    pub recorded: Option<f64>,
+    pub replayed: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abs_diff: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ReplayReport {
+    pub target: String,
+    /// Model versions that produced the replayed predictions
+    pub replayed_versions: Vec<String>,
+    /// Model versions named in the recorded records
+    pub recorded_versions: Vec<String>,
+    pub replayed: usize,
+    pub failed: usize,
+    /// Lines that were not a request or audit record
+    pub skipped_lines: usize,
+    pub latency: LatencySummary,
+    /// Records with a recorded outcome compared against the replay
+    pub compared: usize,
+    pub matching: usize,
+    pub mismatching: usize,
+    pub max_abs_diff: f64,
+    pub mean_abs_diff: f64,
+    /// The first mismatches, in file order
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl ReplayReport {
+    fn record(
+        &mut self,
+        line: usize,
+        recorded: &RecordedPrediction,
+        outcome: &ReplayOutcome,
+        tolerance: f64,
+        diffs: &mut Vec<f64>,
+    ) {
+        self.replayed += 1;
+        if outcome.prediction.is_err() {
+            self.failed += 1;
+        }
+        add_unique(
+            &mut self.recorded_versions,
+            recorded.model_version.as_deref(),
+        );
+        add_unique(
+            &mut self.replayed_versions,
+            outcome.model_version.as_deref(),
+        );
+
+        if !recorded.has_outcome() {
+            return;
+        }
+        self.compared += 1;
+
+        let replayed = outcome.prediction.as_ref().ok().copied();
+        let abs_diff = match (recorded.prediction, replayed) {
+            (Some(recorded), Some(replayed)) => Some((recorded - replayed).abs()),
+            _ => None,
+        };
+        let matches = match abs_diff {
+            Some(diff) => {
+                diffs.push(diff);
+                diff <= tolerance
+            }
+            // Both failed
+            None => recorded.prediction.is_none() && replayed.is_none(),
+        };
+
+        if matches {
+            self.matching += 1;
+            return;
+        }
+        self.mismatching += 1;
+        if self.mismatches.len() < MAX_REPORTED_MISMATCHES {
+            self.mismatches.push(Mismatch {
+                line,
+                request_id: recorded.request_id.clone(),
+                recorded: recorded.prediction,
+                replayed,
+                abs_diff,
+            });
+        }
+    }
+
+    fn print(&self) {
+        println!("Replayed {} records against {}", self.replayed, self.target);
+        println!(
+            "  model versions: replayed [{}], recorded [{}]",
+            self.replayed_versions.join(", "),
+            self.recorded_versions.join(", ")
+        );
+        println!(
+            "  failed: {}, skipped lines: {}",
+            self.failed, self.skipped_lines
+        );
+        println!(
+            "  latency ms: mean {:.3}, p50 {:.3}, p90 {:.3}, p99 {:.3}, max {:.3}",
+            self.latency.mean_ms,
+            self.latency.p50_ms,
+            self.latency.p90_ms,
+            self.latency.p99_ms,
+            self.latency.max_ms
+        );
+        println!(
+            "  compared: {}, matching: {}, mismatching: {}, max abs diff: {:.6}, mean abs diff: {:.6}",
+            self.compared, self.matching, self.mismatching, self.max_abs_diff, self.mean_abs_diff
+        );
+        for mismatch in &self.mismatches {
+            println!(
+                "  line {} ({}): recorded {:?}, replayed {:?}",
+                mismatch.line,
+                mismatch.request_id.as_deref().unwrap_or("-"),
+                mismatch.recorded,
+                mismatch.replayed
+            );
+        }
+    }
+}
+
+fn add_unique(values: &mut Vec<String>, value: Option<&str>) {
+    if let Some(value) = value {
+        if !values.iter().any(|v| v == value) {
+            values.push(value.to_string());
+        }
+    }
+}
+
+enum Target {
+    InProcess(Box<LinearRegressionModel>),
+    Remote {
+        client: reqwest::Client,
+        url: String,
+        api_key: Option<String>,
+    },
+}
+
+impl Target {
+    fn from_args(args: &ReplayArgs) -> Result<Self> {
+        if let Some(base_url) = &args.url {
+            return Ok(Target::Remote {
+                client: reqwest::Client::new(),
+                url: format!("{}/predict", base_url.trim_end_matches('/')),
+                api_key: args.api_key.clone(),
+            });
+        }
+
+        let model = match &args.model {
+            Some(path) => LinearRegressionModel::from_file(path)?,
+            None => LinearRegressionModel::new(),
+        };
+        Ok(Target::InProcess(Box::new(model)))
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Target::InProcess(model) => format!("in-process model {}", model.version()),
+            Target::Remote { url, .. } => url.clone(),
+        }
+    }
+
+    async fn predict(&self, features: Vec<f64>) -> Result<ReplayOutcome> {
+        match self {
+            Target::InProcess(model) => {
+                let started = Instant::now();
+                let result = model.predict(&features);
+                let latency = started.elapsed();
+
+                Ok(ReplayOutcome {
+                    prediction: result
+                        .as_ref()
+                        .map(|r| r.prediction)
+                        .map_err(|e| e.to_string()),
+                    model_version: Some(model.version().to_string()),
+                    latency,
+                })
+            }
+            Target::Remote {
+                client,
+                url,
+                api_key,
+            } => {
+                let mut request = client.post(url).json(&PredictionRequest { features });
+                if let Some(api_key) = api_key {
+                    request = request.header("X-API-Key", api_key);
+                }
+
+                let started = Instant::now();
+                let response = request
+                    .send()
+                    .await
+                    .with_context(|| format!("Failed to reach {}", url))?;
+                let status = response.status();
+                let body: serde_json::Value = response.json().await.unwrap_or_default();
+                let latency = started.elapsed();
+
+                // Every record would fail the same way, so stop early
+                if status == reqwest::StatusCode::UNAUTHORIZED
+                    || status == reqwest::StatusCode::FORBIDDEN
+                {
+                    bail!("{} returned {}; check --api-key", url, status);
+                }
+
+                let prediction = match body["prediction"].as_f64() {
+                    Some(prediction) if status.is_success() => Ok(prediction),
+                    _ => Err(format!("HTTP {}", status)),
+                };
+                Ok(ReplayOutcome {
+                    prediction,
+                    model_version: body["model_version"].as_str().map(str::to_string),
+                    latency,
+                })
+            }
+        }
+    }
+}
+
+/// Replay every record in `args.input` and build the report
+pub async fn replay(args: &ReplayArgs) -> Result<ReplayReport> {
+    let target = Target::from_args(args)?;
+    let records = read_records(&args.input)?;
+
+    let mut report = ReplayReport {
+        target: target.describe(),
+        skipped_lines: records.skipped,
+        ..ReplayReport::default()
+    };
+    let mut latencies = Vec::new();
+    let mut diffs = Vec::new();
+
+    for (line, recorded) in records
+        .records
+        .into_iter()
+        .take(args.limit.unwrap_or(usize::MAX))
+    {
+        let outcome = target.predict(recorded.features.clone()).await?;
+        latencies.push(outcome.latency);
+        report.record(line, &recorded, &outcome, args.tolerance, &mut diffs);
+    }
+
+    report.latency = LatencySummary::from_samples(&latencies);
+    if !diffs.is_empty() {
+        report.max_abs_diff = diffs.iter().copied().fold(0.0, f64::max);
+        report.mean_abs_diff = diffs.iter().sum::<f64>() / diffs.len() as f64;
+    }
+    Ok(report)
+}
+
+/// Entry point for the `replay` subcommand
+pub async fn run(args: ReplayArgs) -> Result<()> {
+    let report = replay(&args).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        report.print();
+    }
+    Ok(())
+}
+
+struct Records {
+    /// Line number (1-based) and record
+    records: Vec<(usize, RecordedPrediction)>,
+    skipped: usize,
+}
+
+fn read_records(path: &Path) -> Result<Records> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open replay input {}", path.display()))?;
+
+    let mut records = Records {
+        records: Vec::new(),
+        skipped: 0,
+    };
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<RecordedPrediction>(&line) {
+            Ok(record) => records.records.push((index + 1, record)),
+            Err(e) => {
+                tracing::debug!(line = index + 1, error = %e, "Skipping line that is not a prediction record");
+                records.skipped += 1;
+            }
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(input: PathBuf) -> ReplayArgs {
+        ReplayArgs {
+            input,
+            url: None,
+            model: None,
+            api_key: None,
+            tolerance: 1e-9,
+            limit: None,
+            json: false,
+        }
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let samples: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        let summary = LatencySummary::from_samples(&samples);
+
+        assert_eq!(summary.count, 100);
+        assert_eq!(summary.p50_ms, 50.0);
+        assert_eq!(summary.p90_ms, 90.0);
+        assert_eq!(summary.p95_ms, 95.0);
+        assert_eq!(summary.p99_ms, 99.0);
+        assert_eq!(summary.max_ms, 100.0);
+        assert_eq!(LatencySummary::from_samples(&[]).count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_replay_diffs_recorded_outputs() {
+        let model = LinearRegressionModel::new();
+        let features = vec![1.0; 10];
+        let expected = model.predict(&features).unwrap().prediction;
+
+        let path = std::env::temp_dir().join(format!("replay-{}.jsonl", uuid::Uuid::new_v4()));
+        let lines = [
+            // Audit record that still matches
+            serde_json::json!({ "request_id": "a", "features": features, "model_version": "v1.0.0", "prediction": expected }).to_string(),
+            // Audit record from a model that predicted differently
+            serde_json::json!({ "request_id": "b", "features": features, "model_version": "v0.9.0", "prediction": expected + 1.0 }).to_string(),
+            // Recorded failure that still fails
+            serde_json::json!({ "request_id": "c", "features": [1.0], "error": "Expected 10 features, got 1" }).to_string(),
+            // Bare request, nothing to compare
+            serde_json::json!({ "features": features }).to_string(),
+            "not json".to_string(),
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let report = replay(&args(path.clone())).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(report.replayed, 4);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.skipped_lines, 1);
+        assert_eq!(report.compared, 3);
+        assert_eq!(report.matching, 2);
+        assert_eq!(report.mismatching, 1);
+        assert_eq!(report.mismatches[0].request_id.as_deref(), Some("b"));
+        assert_eq!(report.mismatches[0].line, 2);
+        assert!((report.max_abs_diff - 1.0).abs() < 1e-9);
+        assert_eq!(report.recorded_versions, vec!["v1.0.0", "v0.9.0"]);
+        assert_eq!(report.replayed_versions, vec!["v1.0.0"]);
+        assert_eq!(report.latency.count, 4);
+    }
+}
@@ -0,0 +1,114 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest incoming `X-Request-Id` accepted before a fresh one is generated
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Correlation ID for a request, available to handlers as an extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Reuse the caller's `X-Request-Id` when it is short printable ASCII,
+    /// otherwise generate a UUID v4
+    fn from_request(request: &Request) -> Self {
+        let incoming = request
+            .headers()
+            .get(&REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|id| {
+                !id.is_empty()
+                    && id.len() <= MAX_REQUEST_ID_LEN
+                    && id.bytes().all(|b| b.is_ascii_graphic())
+            });
+
+        match incoming {
+            Some(id) => Self(id.to_string()),
+            None => Self(Uuid::new_v4().to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Assign a request ID, expose it to handlers and echo it in the response
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = RequestId::from_request(&request);
+    let header_value =
+        HeaderValue::from_str(request_id.as_str()).expect("request ID is printable ASCII");
+    request.extensions_mut().insert(request_id);
+
+    let mut response = next.run(request).await;
+    response
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER.clone(), header_value);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::Extension, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/",
+                get(|Extension(id): Extension<RequestId>| async move { id.0 }),
+            )
+            .layer(middleware::from_fn(request_id_middleware))
+    }
+
+    async fn request_with(header: Option<&str>) -> (String, String) {
+        let mut builder = Request::builder().uri("/");
+        if let Some(value) = header {
+            builder = builder.header(&REQUEST_ID_HEADER, value);
+        }
+        let response = app()
+            .oneshot(builder.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let header = response.headers()[&REQUEST_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (header, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_generates_request_id() {
+        let (header, body) = request_with(None).await;
+        assert!(Uuid::parse_str(&header).is_ok());
+        assert_eq!(header, body);
+    }
+
+    #[tokio::test]
+    async fn test_accepts_incoming_request_id() {
+        let (header, body) = request_with(Some("client-abc-123")).await;
+        assert_eq!(header, "client-abc-123");
+        assert_eq!(body, "client-abc-123");
+    }
+
+    #[tokio::test]
+    async fn test_replaces_invalid_request_id() {
+        let (header, _) = request_with(Some("has spaces")).await;
+        assert!(Uuid::parse_str(&header).is_ok());
+
+        let (header, _) = request_with(Some(&"x".repeat(MAX_REQUEST_ID_LEN + 1))).await;
+        assert!(Uuid::parse_str(&header).is_ok());
+    }
+}
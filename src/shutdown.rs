@@ -0,0 +1,84 @@
+use crate::readiness::Readiness;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Resolve when the process receives SIGTERM or SIGINT (Ctrl-C)
+pub async fn termination_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!(error = %e, "Failed to listen for SIGINT");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("Received SIGINT"),
+        _ = terminate => tracing::info!("Received SIGTERM"),
+    }
+}
+
+/// Shutdown sequence passed to `with_graceful_shutdown`
+///
+/// Once `signal` resolves, readiness starts failing and the listener keeps
+/// serving for `drain_delay` so load balancers can stop routing new traffic.
+/// When this future completes the server stops accepting connections and
+/// `draining` is notified so the caller can bound the wait for in-flight
+/// requests.
+pub async fn drain(
+    signal: impl Future<Output = ()>,
+    readiness: Arc<Readiness>,
+    drain_delay: Duration,
+    draining: Arc<Notify>,
+) {
+    signal.await;
+
+    readiness.set_draining();
+    tracing::info!(
+        drain_delay_secs = drain_delay.as_secs_f64(),
+        "Shutdown started, readiness now failing"
+    );
+    tokio::time::sleep(drain_delay).await;
+
+    tracing::info!("Drain delay elapsed, waiting for in-flight requests");
+    draining.notify_one();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_fails_readiness_before_completing() {
+        let readiness = Arc::new(Readiness::new(Vec::new()));
+        let draining = Arc::new(Notify::new());
+        let drain_delay = Duration::from_millis(50);
+
+        let started = std::time::Instant::now();
+        drain(async {}, readiness.clone(), drain_delay, draining.clone()).await;
+
+        assert!(readiness.is_draining());
+        assert!(started.elapsed() >= drain_delay);
+        // The notification is stored for a waiter registered afterwards
+        tokio::time::timeout(Duration::from_millis(10), draining.notified())
+            .await
+            .expect("draining was not notified");
+    }
+}
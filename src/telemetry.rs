@@ -0,0 +1,118 @@
+use crate::config::TelemetryConfig;
+use crate::metrics::prometheus::method_label;
+use crate::request_id::RequestId;
+use anyhow::{Context, Result};
+use axum::{
+    extract::{MatchedPath, Request},
+    http::HeaderMap,
+};
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use std::time::Duration;
+use tracing::Span;
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Build a tracer provider that batches spans to the configured OTLP/HTTP collector
+///
+/// Call `shutdown` on the provider before exiting so buffered spans are flushed.
+pub fn init_tracer_provider(
+    config: &TelemetryConfig,
+    service_name: &str,
+) -> Result<SdkTracerProvider> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(&config.otlp_endpoint)
+        .with_timeout(Duration::from_millis(config.export_timeout_ms))
+        .build()
+        .context("Failed to build OTLP span exporter")?;
+
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio)));
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(sampler)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(service_name.to_string())
+                .build(),
+        )
+        .build())
+}
+
+/// `tracing` layer exporting spans through `provider`
+pub fn layer<S>(
+    provider: &SdkTracerProvider,
+) -> OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+}
+
+/// Reads W3C trace context from request headers
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
+
+/// Continue the caller's trace when the request carries a valid `traceparent`
+///
+/// Without one (or without the OpenTelemetry layer installed) the span stays
+/// a root span.
+pub fn set_remote_parent(span: &Span, headers: &HeaderMap) {
+    let context = TraceContextPropagator::new().extract(&HeaderExtractor(headers));
+    if let Err(e) = span.set_parent(context) {
+        tracing::debug!(error = %e, "Could not attach remote trace context");
+    }
+}
+
+/// Trace ID of the current span, if OpenTelemetry is recording it
+pub fn current_trace_id() -> Option<String> {
+    let context = Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    (span_context.is_valid() && span_context.is_sampled())
+        .then(|| span_context.trace_id().to_string())
+}
+
+/// Span for `TraceLayer` covering the whole HTTP exchange
+///
+/// Carries the request ID so every log line emitted while handling the
+/// request can be correlated with it, and continues any incoming W3C trace.
+/// Created at `INFO` so it is recorded under the default log filter. Named
+/// after the matched route template, like the metrics `endpoint` label, so
+/// raw paths cannot grow the number of span names.
+pub fn make_request_span(request: &Request) -> Span {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", MatchedPath::as_str);
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(RequestId::as_str)
+        .unwrap_or_default();
+
+    let span = tracing::info_span!(
+        "request",
+        otel.name = %format!("{} {}", method_label(request.method().as_str()), route),
+        otel.kind = "server",
+        method = %request.method(),
+        uri = %request.uri(),
+        request_id = %request_id,
+    );
+    set_remote_parent(&span, request.headers());
+    span
+}
@@ -0,0 +1,265 @@
+use crate::config::TlsConfig;
+use anyhow::{anyhow, Context, Result};
+use axum::http::Request;
+use axum_server::accept::Accept;
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use std::time::SystemTime;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tower::Service;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Identity of a client that authenticated with a certificate (mTLS)
+///
+/// Inserted as a request extension; handlers can extract it with
+/// `Option<Extension<ClientIdentity>>`.
+#[derive(Debug, Clone)]
+pub struct ClientIdentity {
+    /// Full subject distinguished name
+    pub subject: String,
+    pub common_name: Option<String>,
+    /// DNS, URI and email subject alternative names
+    pub subject_alt_names: Vec<String>,
+    /// SHA-256 of the DER-encoded certificate
+    pub fingerprint: String,
+}
+
+impl ClientIdentity {
+    /// Parse the identity from a DER-encoded X.509 certificate
+    pub fn from_der(der: &[u8]) -> Result<Self> {
+        let (_, certificate) = X509Certificate::from_der(der)
+            .map_err(|e| anyhow!("Invalid client certificate: {}", e))?;
+
+        let subject = certificate.subject();
+        let common_name = subject
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .map(str::to_string);
+
+        let subject_alt_names = certificate
+            .subject_alternative_name()
+            .ok()
+            .flatten()
+            .map(|san| {
+                san.value
+                    .general_names
+                    .iter()
+                    .filter_map(|name| match name {
+                        GeneralName::DNSName(dns) => Some(format!("DNS:{}", dns)),
+                        GeneralName::URI(uri) => Some(format!("URI:{}", uri)),
+                        GeneralName::RFC822Name(email) => Some(format!("email:{}", email)),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            subject: subject.to_string(),
+            common_name,
+            subject_alt_names,
+            fingerprint: format!("sha256:{:x}", Sha256::digest(der)),
+        })
+    }
+
+    /// Short name for logging: the common name, else the first SAN, else the fingerprint
+    pub fn display_name(&self) -> &str {
+        self.common_name
+            .as_deref()
+            .or(self.subject_alt_names.first().map(String::as_str))
+            .unwrap_or(&self.fingerprint)
+    }
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let pem = std::fs::read(path)
+        .with_context(|| format!("Failed to read certificate {}", path.display()))?;
+    let certs = rustls_pemfile::certs(&mut pem.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid PEM certificate {}", path.display()))?;
+
+    if certs.is_empty() {
+        return Err(anyhow!("No certificates found in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let pem = std::fs::read(path)
+        .with_context(|| format!("Failed to read private key {}", path.display()))?;
+
+    rustls_pemfile::private_key(&mut pem.as_slice())
+        .with_context(|| format!("Invalid PEM private key {}", path.display()))?
+        .ok_or_else(|| anyhow!("No private key found in {}", path.display()))
+}
+
+/// Build the rustls server configuration from the certificate files on disk
+///
+/// When `client_ca_path` is set, clients must present a certificate signed
+/// by that CA (mutual TLS).
+pub fn load_server_config(tls: &TlsConfig) -> Result<Arc<ServerConfig>> {
+    let (Some(cert_path), Some(key_path)) = (&tls.cert_path, &tls.key_path) else {
+        return Err(anyhow!("tls.cert_path and tls.key_path are required"));
+    };
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .context("Unsupported TLS protocol configuration")?;
+
+    let builder = match &tls.client_ca_path {
+        Some(ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(ca_path)? {
+                roots
+                    .add(cert)
+                    .with_context(|| format!("Invalid client CA {}", ca_path.display()))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                .build()
+                .context("Failed to build client certificate verifier")?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut config = builder
+        .with_single_cert(load_certs(cert_path)?, load_key(key_path)?)
+        .context("Server certificate does not match private key")?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(Arc::new(config))
+}
+
+fn modified_times(tls: &TlsConfig) -> Vec<Option<SystemTime>> {
+    [&tls.cert_path, &tls.key_path, &tls.client_ca_path]
+        .into_iter()
+        .map(|path| {
+            path.as_ref()
+                .and_then(|p| std::fs::metadata(p).ok())
+                .and_then(|m| m.modified().ok())
+        })
+        .collect()
+}
+
+/// Watch the certificate, key and client CA files and reload them on change
+///
+/// A failed reload is logged and the previous certificates stay in use.
+pub fn spawn_reloader(tls: TlsConfig, rustls_config: RustlsConfig) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut last_modified = modified_times(&tls);
+        let mut interval = tokio::time::interval(tls.reload_interval());
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let modified = modified_times(&tls);
+            if modified == last_modified {
+                continue;
+            }
+
+            match load_server_config(&tls) {
+                Ok(server_config) => {
+                    rustls_config.reload_from_config(server_config);
+                    last_modified = modified;
+                    tracing::info!("TLS certificates reloaded");
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to reload TLS certificates, keeping previous");
+                }
+            }
+        }
+    })
+}
+
+/// TLS acceptor that exposes the verified client certificate to handlers
+#[derive(Clone)]
+pub struct ClientIdentityAcceptor {
+    inner: RustlsAcceptor,
+}
+
+impl ClientIdentityAcceptor {
+    pub fn new(config: RustlsConfig) -> Self {
+        Self {
+            inner: RustlsAcceptor::new(config),
+        }
+    }
+}
+
+impl<I, S> Accept<I, S> for ClientIdentityAcceptor
+where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = <RustlsAcceptor as Accept<I, S>>::Stream;
+    type Service = WithClientIdentity<S>;
+    type Future = Pin<Box<dyn Future<Output = io::Result<(Self::Stream, Self::Service)>> + Send>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let accept = self.inner.accept(stream, service);
+
+        Box::pin(async move {
+            let (stream, service) = accept.await?;
+
+            let identity = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(|certs| certs.first())
+                .and_then(|cert| match ClientIdentity::from_der(cert) {
+                    Ok(identity) => Some(Arc::new(identity)),
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Could not parse client certificate");
+                        None
+                    }
+                });
+
+            if let Some(identity) = &identity {
+                tracing::debug!(client = %identity.display_name(), "Client certificate accepted");
+            }
+
+            Ok((stream, WithClientIdentity { service, identity }))
+        })
+    }
+}
+
+/// Per-connection service that inserts the client identity into each request
+#[derive(Clone)]
+pub struct WithClientIdentity<S> {
+    service: S,
+    identity: Option<Arc<ClientIdentity>>,
+}
+
+impl<S, B> Service<Request<B>> for WithClientIdentity<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        if let Some(identity) = &self.identity {
+            request
+                .extensions_mut()
+                .insert(ClientIdentity::clone(identity));
+        }
+        self.service.call(request)
+    }
+}
@@ -1,5 +1,8 @@
 #[cfg(test)]
 mod integration_tests {
+    use ai_model_service::app::{self, AppState};
+    use ai_model_service::config::Config;
+    use ai_model_service::models::ml_model;
     use axum::{
         body::Body,
         http::{Request, StatusCode},
@@ -10,17 +13,20 @@ mod integration_tests {
 
     // Helper function to create the test app
     async fn create_test_app() -> Router {
-        use ai_model_service::handlers::{health, predict};
-        use ai_model_service::metrics::prometheus;
-        use axum::routing::{get, post};
+        let state = AppState::new(Config::default()).unwrap();
+        load_model();
+        state.readiness.set_model_ready();
 
-        // Initialize metrics (required for the app to work)
-        prometheus::setup_metrics_recorder().expect("Failed to setup metrics");
+        create_test_app_with_state(state)
+    }
+
+    fn load_model() {
+        ml_model::load_model(None).expect("Failed to load model");
+        ml_model::warm_up().expect("Failed to warm up model");
+    }
 
-        Router::new()
-            .route("/health", get(health::health_check))
-            .route("/predict", post(predict::predict))
-            .route("/metrics", get(prometheus::metrics_handler))
+    fn create_test_app_with_state(state: AppState) -> Router {
+        app::create_router(state)
     }
 
     #[tokio::test]
@@ -50,6 +56,62 @@ mod integration_tests {
         assert!(health_response["timestamp"].is_string());
     }
 
+    #[tokio::test]
+    async fn test_liveness_endpoint() {
+        let app = create_test_app_with_state(AppState::new(Config::default()).unwrap());
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("/livez")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+
+        // Liveness does not depend on the model being warmed
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_endpoint() {
+        let state = AppState::new(Config::default()).unwrap();
+        let app = create_test_app_with_state(state.clone());
+
+        let readyz = || {
+            Request::builder()
+                .method("GET")
+                .uri("/readyz")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // Not ready until the model is warmed
+        let response = app.clone().oneshot(readyz()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let readiness: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(readiness["status"], "not_ready");
+        let model_check = readiness["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["name"] == "model")
+            .unwrap();
+        assert_eq!(model_check["status"], "fail");
+
+        state.readiness.set_model_ready();
+        let response = app.clone().oneshot(readyz()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Draining flips readiness back to failing
+        state.readiness.set_draining();
+        let response = app.oneshot(readyz()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
     #[tokio::test]
     async fn test_predict_endpoint_valid_input() {
         let app = create_test_app().await;
@@ -84,6 +146,60 @@ mod integration_tests {
         assert!((0.85..=1.0).contains(&confidence));
     }
 
+    #[tokio::test]
+    async fn test_predict_request_id_correlation() {
+        let app = create_test_app().await;
+
+        let request_body = json!({
+            "features": [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]
+        });
+
+        // Generated when the client sends none
+        let request = Request::builder()
+            .method("POST")
+            .uri("/predict")
+            .header("content-type", "application/json")
+            .body(Body::from(request_body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let header = response.headers()["x-request-id"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let prediction_response: Value = serde_json::from_slice(&body).unwrap();
+        assert!(uuid::Uuid::parse_str(&header).is_ok());
+        assert_eq!(prediction_response["prediction_id"], header);
+
+        // Propagated when the client sends one
+        let request = Request::builder()
+            .method("POST")
+            .uri("/predict")
+            .header("content-type", "application/json")
+            .header("x-request-id", "client-req-42")
+            .body(Body::from(request_body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()["x-request-id"], "client-req-42");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let prediction_response: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(prediction_response["prediction_id"], "client-req-42");
+
+        // Errors carry it too
+        let request = Request::builder()
+            .uri("/models/unknown")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(response.headers().contains_key("x-request-id"));
+    }
+
     #[tokio::test]
     async fn test_predict_endpoint_invalid_feature_count() {
         let app = create_test_app().await;
@@ -142,7 +258,33 @@ mod integration_tests {
 
     #[tokio::test]
     async fn test_metrics_endpoint() {
-        let app = create_test_app().await;
+        let state = AppState::new(Config::default()).unwrap();
+        load_model();
+        state.readiness.set_model_ready();
+        let metrics = state.metrics.clone();
+        let app = create_test_app_with_state(state);
+
+        // A second instance in the same process keeps its own counts
+        let other = create_test_app().await;
+        other.oneshot(predict_request(None)).await.unwrap();
+
+        let response = app.clone().oneshot(predict_request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            metrics
+                .ml_predictions_total
+                .with_label_values(&["v1.0.0", "success"])
+                .get(),
+            1.0
+        );
+        assert_eq!(
+            metrics
+                .http_requests_total
+                .with_label_values(&["POST", "/predict", "200"])
+                .get(),
+            1.0
+        );
+        assert_eq!(metrics.http_requests_in_flight.get(), 0.0);
 
         let request = Request::builder()
             .method("GET")
@@ -165,6 +307,126 @@ mod integration_tests {
         // Check that metrics response is not empty and contains essential metrics
         assert!(!body_str.is_empty());
         assert!(body_str.contains("service_uptime_seconds"));
+        assert!(body_str
+            .contains("ml_predictions_total{model_version=\"v1.0.0\",status=\"success\"} 1\n"));
+        assert!(body_str.contains("ml_prediction_value_count{model_version=\"v1.0.0\"} 1\n"));
+        assert!(body_str
+            .contains("ml_prediction_value_quantile{model_version=\"v1.0.0\",quantile=\"0.5\"}"));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_openmetrics_exemplars() {
+        let app = create_test_app().await;
+
+        let response = app
+            .clone()
+            .oneshot(predict_request(Some(("x-request-id", "exemplar-req-1"))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("/metrics")
+            .header("accept", "application/openmetrics-text; version=1.0.0")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("application/openmetrics-text"));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+
+        // Without OpenTelemetry the exemplar carries only the request ID
+        let exemplar = "# {request_id=\"exemplar-req-1\"}";
+        let has_exemplar = |prefix: &str| {
+            text.lines()
+                .any(|line| line.starts_with(prefix) && line.contains(exemplar))
+        };
+        assert!(has_exemplar(
+            "http_request_duration_seconds_bucket{endpoint=\"/predict\",method=\"POST\""
+        ));
+        assert!(has_exemplar("ml_prediction_confidence_bucket"));
+        assert!(text.contains("# TYPE http_requests counter\n"));
+        assert!(text.ends_with("# EOF\n"));
+    }
+
+    #[tokio::test]
+    async fn test_model_drift_report() {
+        let app = create_test_app().await;
+        let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let response = app.clone().oneshot(predict_request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(get("/models/linear-regression/drift"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["window_samples"], 1);
+        // The built-in model has no reference profile, so only statistics are reported
+        assert_eq!(report["reference_available"], false);
+        assert_eq!(report["features"].as_array().unwrap().len(), 10);
+        assert_eq!(report["features"][0]["name"], "feature_0");
+        assert_eq!(report["features"][0]["mean"], 1.0);
+        assert!(report["features"][0]["psi"].is_null());
+
+        let response = app.oneshot(get("/models/unknown/drift")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let mut config = Config::default();
+        config.drift.enabled = false;
+        let app = create_test_app_with_state(AppState::new(config).unwrap());
+        let response = app
+            .oneshot(get("/models/linear-regression/drift"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_slo_report() {
+        let app = create_test_app().await;
+        let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let response = app.clone().oneshot(predict_request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        // Other routes do not count towards the SLOs
+        app.clone().oneshot(get("/health")).await.unwrap();
+
+        let response = app.oneshot(get("/slo")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["route"], "/predict");
+        let availability = &report["objectives"][0];
+        assert_eq!(availability["name"], "availability");
+        assert_eq!(availability["windows"][0]["window"], "5m");
+        assert_eq!(availability["windows"][0]["requests"], 1);
+        assert_eq!(availability["windows"][0]["bad"], 0);
+        assert_eq!(availability["windows"][0]["burn_rate"], 0.0);
+        assert_eq!(availability["alerts"][0]["firing"], false);
+        assert_eq!(report["objectives"][1]["latency_threshold_ms"], 250);
+
+        let mut config = Config::default();
+        config.slo.enabled = false;
+        let app = create_test_app_with_state(AppState::new(config).unwrap());
+        let response = app.oneshot(get("/slo")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
     }
 
     #[tokio::test]
@@ -220,6 +482,368 @@ mod integration_tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_models_endpoint() {
+        let app = create_test_app().await;
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("/models")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let models_response: Value = serde_json::from_slice(&body).unwrap();
+
+        let models = models_response["models"].as_array().unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0]["name"], "linear-regression");
+        assert_eq!(models[0]["version"], "v1.0.0");
+        assert_eq!(models[0]["feature_count"], 10);
+        assert_eq!(models[0]["feature_names"].as_array().unwrap().len(), 10);
+        assert!(models[0]["loaded_at"].is_string());
+        assert!(models[0]["checksum"]
+            .as_str()
+            .unwrap()
+            .starts_with("sha256:"));
+    }
+
+    #[tokio::test]
+    async fn test_model_metadata_endpoint() {
+        let app = create_test_app().await;
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("/models/linear-regression")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let metadata: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(metadata["model_type"], "linear_regression");
+        assert_eq!(metadata["output_type"], "continuous");
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("/models/unknown-model")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    /// Resolve a `$ref` to `#/components/schemas/...` within the spec
+    fn resolve_schema<'a>(spec: &'a Value, schema: &'a Value) -> &'a Value {
+        match schema["$ref"].as_str() {
+            Some(reference) => {
+                let name = reference.trim_start_matches("#/components/schemas/");
+                &spec["components"]["schemas"][name]
+            }
+            None => schema,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_openapi_spec_matches_router() {
+        let app = create_test_app().await;
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("/openapi.json")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let spec: Value = serde_json::from_slice(&body).unwrap();
+
+        // The served spec must match the published copy in docs/openapi.json.
+        // Regenerate it with `UPDATE_OPENAPI_SPEC=1 cargo test`.
+        let published_path = concat!(env!("CARGO_MANIFEST_DIR"), "/docs/openapi.json");
+        if std::env::var_os("UPDATE_OPENAPI_SPEC").is_some() {
+            let pretty = serde_json::to_string_pretty(&spec).unwrap();
+            std::fs::write(published_path, pretty + "\n").unwrap();
+        }
+        let published: Value =
+            serde_json::from_str(&std::fs::read_to_string(published_path).unwrap()).unwrap();
+        assert_eq!(
+            spec, published,
+            "docs/openapi.json is out of date; rerun tests with UPDATE_OPENAPI_SPEC=1"
+        );
+
+        // Every documented operation must be routed, and documented JSON
+        // responses must carry the fields the schema marks as required
+        let paths = spec["paths"].as_object().unwrap();
+        assert!(!paths.is_empty());
+        for (path, operations) in paths {
+            for (method, operation) in operations.as_object().unwrap() {
+                let mut uri = path.clone();
+                for param in operation["parameters"].as_array().into_iter().flatten() {
+                    let name = param["name"].as_str().unwrap();
+                    let example = param["example"]
+                        .as_str()
+                        .unwrap_or_else(|| panic!("parameter {name} of {path} needs an example"));
+                    uri = uri.replace(&format!("{{{name}}}"), example);
+                }
+
+                let request = Request::builder()
+                    .method(method.to_uppercase().as_str())
+                    .uri(&uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from("{}"))
+                    .unwrap();
+
+                let response = app.clone().oneshot(request).await.unwrap();
+                let status = response.status();
+                assert_ne!(
+                    status,
+                    StatusCode::NOT_FOUND,
+                    "{method} {path} is not routed"
+                );
+                assert_ne!(
+                    status,
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    "{method} {path} is not routed"
+                );
+
+                let documented = &operation["responses"][status.as_str()];
+                assert!(
+                    documented.is_object(),
+                    "{method} {path} returned undocumented status {status}"
+                );
+
+                let schema = &documented["content"]["application/json"]["schema"];
+                if schema.is_null() {
+                    continue;
+                }
+                let schema = resolve_schema(&spec, schema);
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                let json: Value = serde_json::from_slice(&body).unwrap();
+                for field in schema["required"].as_array().into_iter().flatten() {
+                    let field = field.as_str().unwrap();
+                    assert!(
+                        !json[field].is_null(),
+                        "{method} {path} response is missing documented field {field}"
+                    );
+                }
+                let properties = schema["properties"].as_object().unwrap();
+                for field in json.as_object().unwrap().keys() {
+                    assert!(
+                        properties.contains_key(field),
+                        "{method} {path} response has undocumented field {field}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_docs_ui_endpoint() {
+        let app = create_test_app().await;
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("/docs/")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    fn create_auth_test_app() -> Router {
+        use ai_model_service::config::{ApiKeyConfig, JwtConfig};
+        use sha2::{Digest, Sha256};
+
+        let mut config = Config::default();
+        config.auth.enabled = true;
+        config.auth.api_keys = vec![ApiKeyConfig {
+            name: "batch-client".to_string(),
+            key_sha256: format!("{:x}", Sha256::digest(b"integration-test-key")),
+            roles: vec![],
+            tier: None,
+        }];
+        config.auth.jwt = Some(JwtConfig {
+            jwks_path: concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/jwks.json").into(),
+            issuer: None,
+            audience: Some("ai-model-service".to_string()),
+            leeway_secs: 0,
+        });
+
+        let state = AppState::new(config).unwrap();
+        load_model();
+        state.readiness.set_model_ready();
+        create_test_app_with_state(state)
+    }
+
+    fn predict_request(header: Option<(&str, &str)>) -> Request<Body> {
+        let request_body = json!({
+            "features": [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]
+        });
+
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/predict")
+            .header("content-type", "application/json");
+        if let Some((name, value)) = header {
+            builder = builder.header(name, value);
+        }
+        builder.body(Body::from(request_body.to_string())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_auth_open_routes_and_api_key() {
+        let app = create_auth_test_app();
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("/health")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app.clone().oneshot(predict_request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(response.headers().contains_key("www-authenticate"));
+
+        let response = app
+            .clone()
+            .oneshot(predict_request(Some(("x-api-key", "wrong-key"))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Authentication runs before the request limits, so an anonymous
+        // oversized request is refused without being admitted
+        let request = Request::builder()
+            .method("POST")
+            .uri("/predict")
+            .header("content-type", "application/json")
+            .header("content-length", "1073741824")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .oneshot(predict_request(Some(("x-api-key", "integration-test-key"))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_auth_rs256_jwt() {
+        use jsonwebtoken::{Algorithm, EncodingKey, Header};
+
+        let app = create_auth_test_app();
+        let private_key = include_bytes!("fixtures/jwt_rs256_private.pem");
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some("rs256-test".to_string());
+
+        let sign = |audience: &str| {
+            let claims = json!({
+                "sub": "dashboard",
+                "aud": audience,
+                "exp": chrono::Utc::now().timestamp() + 60,
+            });
+            jsonwebtoken::encode(
+                &header,
+                &claims,
+                &EncodingKey::from_rsa_pem(private_key).unwrap(),
+            )
+            .unwrap()
+        };
+
+        let token = format!("Bearer {}", sign("ai-model-service"));
+        let response = app
+            .clone()
+            .oneshot(predict_request(Some(("authorization", &token))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let token = format!("Bearer {}", sign("another-service"));
+        let response = app
+            .oneshot(predict_request(Some(("authorization", &token))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_predict() {
+        use ai_model_service::config::RateLimitTierConfig;
+        use axum::extract::connect_info::MockConnectInfo;
+        use std::net::SocketAddr;
+
+        let mut config = Config::default();
+        config.rate_limit.enabled = true;
+        config.rate_limit.anonymous_tier = "integration-test".to_string();
+        config.rate_limit.tiers.insert(
+            "integration-test".to_string(),
+            RateLimitTierConfig {
+                burst: 2,
+                refill_per_sec: 0.5,
+            },
+        );
+        let state = AppState::new(config).unwrap();
+        load_model();
+        state.readiness.set_model_ready();
+        let metrics = state.metrics.clone();
+        let app = create_test_app_with_state(state)
+            .layer(MockConnectInfo(SocketAddr::from(([192, 0, 2, 1], 4000))));
+
+        for remaining in ["1", "0"] {
+            let response = app.clone().oneshot(predict_request(None)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()["ratelimit-limit"], "2");
+            assert_eq!(response.headers()["ratelimit-remaining"], remaining);
+        }
+
+        let response = app.clone().oneshot(predict_request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["retry-after"], "2");
+        assert_eq!(response.headers()["ratelimit-remaining"], "0");
+
+        // Only /predict is limited
+        let request = Request::builder()
+            .method("GET")
+            .uri("/health")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        assert_eq!(
+            metrics
+                .rate_limited_requests_total
+                .with_label_values(&["integration-test"])
+                .get(),
+            1.0
+        );
+    }
+
     #[tokio::test]
     async fn test_nonexistent_endpoint() {
         let app = create_test_app().await;
@@ -234,4 +858,499 @@ mod integration_tests {
 
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
+
+    #[tokio::test]
+    async fn test_prediction_audit_log() {
+        use ai_model_service::audit;
+        use ai_model_service::config::AuditConfig;
+
+        let dir = std::env::temp_dir().join(format!("audit-{}", uuid::Uuid::new_v4()));
+        let config = AuditConfig {
+            enabled: true,
+            path: dir.join("predictions.ndjson"),
+            ..AuditConfig::default()
+        };
+        let mut state = AppState::new(Config::default()).unwrap();
+        let (auditor, writer) = audit::spawn(&config, state.metrics.clone()).unwrap();
+        state.auditor = Some(auditor);
+        load_model();
+        let app = create_test_app_with_state(state);
+
+        let response = app.clone().oneshot(predict_request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let prediction: Value = serde_json::from_slice(&body).unwrap();
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/predict")
+            .header("content-type", "application/json")
+            .body(Body::from(json!({ "features": [1.0] }).to_string()))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        writer.shutdown().await.unwrap();
+        let contents = std::fs::read_to_string(&config.path).unwrap();
+        let records: Vec<Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["request_id"], prediction["prediction_id"]);
+        assert_eq!(records[0]["prediction"], prediction["prediction"]);
+        assert_eq!(records[0]["model_version"], "v1.0.0");
+        assert_eq!(records[0]["features"].as_array().unwrap().len(), 10);
+        assert!(records[0]["latency_ms"].is_number());
+        assert!(records[1]["error"].is_string());
+        assert!(records[1].get("prediction").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_replay_against_running_service() {
+        use ai_model_service::replay::{self, ReplayArgs};
+
+        let app = create_test_app().await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let path = std::env::temp_dir().join(format!("replay-{}.jsonl", uuid::Uuid::new_v4()));
+        let features = vec![1.0; 10];
+        let lines = [
+            json!({ "request_id": "a", "features": features, "prediction": 0.0 }).to_string(),
+            json!({ "features": features }).to_string(),
+            json!({ "features": [1.0] }).to_string(),
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let report = replay::replay(&ReplayArgs {
+            input: path.clone(),
+            url: Some(format!("http://{}", address)),
+            model: None,
+            api_key: None,
+            tolerance: 1e-9,
+            limit: None,
+            json: false,
+        })
+        .await
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(report.replayed, 3);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.compared, 1);
+        assert_eq!(report.mismatching, 1);
+        assert_eq!(report.replayed_versions, vec!["v1.0.0"]);
+        assert!(report.latency.p50_ms > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_admin_log_level() {
+        use ai_model_service::config::ApiKeyConfig;
+        use ai_model_service::logging;
+        use sha2::{Digest, Sha256};
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let api_key = |name: &str, key: &str, roles: Vec<String>| ApiKeyConfig {
+            name: name.to_string(),
+            key_sha256: format!("{:x}", Sha256::digest(key.as_bytes())),
+            roles,
+            tier: None,
+        };
+        let mut config = Config::default();
+        config.auth.enabled = true;
+        config.auth.api_keys = vec![
+            api_key("operator", "admin-key", vec!["admin".to_string()]),
+            api_key("batch-client", "client-key", vec![]),
+        ];
+
+        // The reload handle only works while its layer is installed somewhere
+        let (filter_layer, log_level) = logging::filter_layer("info").unwrap();
+        let _subscriber = tracing_subscriber::registry().with(filter_layer);
+        let mut state = AppState::new(config).unwrap();
+        state.log_level = Some(log_level.clone());
+        let app = create_test_app_with_state(state);
+
+        let put = |key: &str, filter: &str| {
+            Request::builder()
+                .method("PUT")
+                .uri("/admin/log-level")
+                .header("content-type", "application/json")
+                .header("x-api-key", key)
+                .body(Body::from(json!({ "filter": filter }).to_string()))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(put("client-key", "debug"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(log_level.current(), "info");
+
+        let response = app
+            .clone()
+            .oneshot(put("admin-key", "warn,ai_model_service=debug"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(log_level.current(), "warn,ai_model_service=debug");
+
+        let response = app
+            .clone()
+            .oneshot(put("admin-key", "not a [valid filter"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let request = Request::builder()
+            .uri("/admin/log-level")
+            .header("x-api-key", "admin-key")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let level: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(level["filter"], "warn,ai_model_service=debug");
+        // Without authentication nobody can hold the admin role
+        let (_, log_level) = logging::filter_layer("info").unwrap();
+        let mut state = AppState::new(Config::default()).unwrap();
+        state.log_level = Some(log_level.clone());
+        let response = create_test_app_with_state(state)
+            .oneshot(put("admin-key", "trace"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(log_level.current(), "info");
+    }
+
+    mod tls {
+        use ai_model_service::config::TlsConfig;
+        use ai_model_service::tls::{self, ClientIdentity, ClientIdentityAcceptor};
+        use axum::{extract::Extension, routing::get, Router};
+        use axum_server::tls_rustls::RustlsConfig;
+        use rcgen::{
+            BasicConstraints, Certificate, CertificateParams, DnType, ExtendedKeyUsagePurpose,
+            IsCa, KeyPair,
+        };
+        use rustls::pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer, ServerName};
+        use rustls::{ClientConfig, RootCertStore};
+        use std::net::SocketAddr;
+        use std::path::PathBuf;
+        use std::sync::Arc;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio_rustls::TlsConnector;
+
+        struct Pki {
+            dir: PathBuf,
+            ca: Certificate,
+            ca_key: KeyPair,
+        }
+
+        impl Pki {
+            fn new() -> Self {
+                let dir = std::env::temp_dir().join(format!("tls-{}", uuid::Uuid::new_v4()));
+                std::fs::create_dir_all(&dir).unwrap();
+
+                let ca_key = KeyPair::generate().unwrap();
+                let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+                params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+                params
+                    .distinguished_name
+                    .push(DnType::CommonName, "Test CA");
+                let ca = params.self_signed(&ca_key).unwrap();
+                std::fs::write(dir.join("ca.pem"), ca.pem()).unwrap();
+
+                Self { dir, ca, ca_key }
+            }
+
+            /// Issue a leaf certificate and return its (cert, key) PEM paths
+            fn issue(
+                &self,
+                name: &str,
+                sans: Vec<String>,
+                usage: ExtendedKeyUsagePurpose,
+            ) -> (PathBuf, PathBuf) {
+                let key = KeyPair::generate().unwrap();
+                let mut params = CertificateParams::new(sans).unwrap();
+                params.distinguished_name.push(DnType::CommonName, name);
+                params.extended_key_usages = vec![usage];
+                let cert = params.signed_by(&key, &self.ca, &self.ca_key).unwrap();
+
+                let cert_path = self.dir.join(format!("{name}.pem"));
+                let key_path = self.dir.join(format!("{name}-key.pem"));
+                std::fs::write(&cert_path, cert.pem()).unwrap();
+                std::fs::write(&key_path, key.serialize_pem()).unwrap();
+                (cert_path, key_path)
+            }
+        }
+
+        impl Drop for Pki {
+            fn drop(&mut self) {
+                let _ = std::fs::remove_dir_all(&self.dir);
+            }
+        }
+
+        async fn whoami(client: Option<Extension<ClientIdentity>>) -> String {
+            client
+                .and_then(|Extension(c)| c.common_name)
+                .unwrap_or_else(|| "anonymous".to_string())
+        }
+
+        async fn start_server(tls_config: &TlsConfig) -> SocketAddr {
+            let rustls_config =
+                RustlsConfig::from_config(tls::load_server_config(tls_config).unwrap());
+            let handle = axum_server::Handle::new();
+            let app = Router::new().route("/whoami", get(whoami));
+
+            let server = axum_server::bind("127.0.0.1:0".parse().unwrap())
+                .acceptor(ClientIdentityAcceptor::new(rustls_config))
+                .handle(handle.clone());
+            tokio::spawn(server.serve(app.into_make_service()));
+
+            handle.listening().await.unwrap()
+        }
+
+        /// Send `GET /whoami` over TLS and return the raw HTTP response
+        async fn get_whoami(
+            address: SocketAddr,
+            pki: &Pki,
+            client_cert: Option<(PathBuf, PathBuf)>,
+        ) -> std::io::Result<String> {
+            let mut roots = RootCertStore::empty();
+            roots
+                .add(CertificateDer::from_pem_file(pki.dir.join("ca.pem")).unwrap())
+                .unwrap();
+
+            let builder = ClientConfig::builder_with_provider(Arc::new(
+                rustls::crypto::ring::default_provider(),
+            ))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots);
+            let config = match client_cert {
+                Some((cert, key)) => builder
+                    .with_client_auth_cert(
+                        vec![CertificateDer::from_pem_file(cert).unwrap()],
+                        PrivateKeyDer::from_pem_file(key).unwrap(),
+                    )
+                    .unwrap(),
+                None => builder.with_no_client_auth(),
+            };
+
+            let tcp = tokio::net::TcpStream::connect(address).await?;
+            let mut stream = TlsConnector::from(Arc::new(config))
+                .connect(ServerName::try_from("localhost").unwrap(), tcp)
+                .await?;
+
+            stream
+                .write_all(b"GET /whoami HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .await?;
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await?;
+            Ok(response)
+        }
+
+        #[tokio::test]
+        async fn test_tls_without_client_auth() {
+            let pki = Pki::new();
+            let (cert_path, key_path) = pki.issue(
+                "server",
+                vec!["localhost".to_string()],
+                ExtendedKeyUsagePurpose::ServerAuth,
+            );
+            let tls_config = TlsConfig {
+                enabled: true,
+                cert_path: Some(cert_path),
+                key_path: Some(key_path),
+                ..TlsConfig::default()
+            };
+
+            let address = start_server(&tls_config).await;
+            let response = get_whoami(address, &pki, None).await.unwrap();
+
+            assert!(response.starts_with("HTTP/1.1 200"));
+            assert!(response.ends_with("anonymous"));
+        }
+
+        #[tokio::test]
+        async fn test_mtls_exposes_client_identity() {
+            let pki = Pki::new();
+            let (cert_path, key_path) = pki.issue(
+                "server",
+                vec!["localhost".to_string()],
+                ExtendedKeyUsagePurpose::ServerAuth,
+            );
+            let client = pki.issue(
+                "test-client",
+                Vec::new(),
+                ExtendedKeyUsagePurpose::ClientAuth,
+            );
+            let tls_config = TlsConfig {
+                enabled: true,
+                cert_path: Some(cert_path),
+                key_path: Some(key_path),
+                client_ca_path: Some(pki.dir.join("ca.pem")),
+                ..TlsConfig::default()
+            };
+
+            let address = start_server(&tls_config).await;
+
+            let response = get_whoami(address, &pki, Some(client)).await.unwrap();
+            assert!(response.starts_with("HTTP/1.1 200"));
+            assert!(response.ends_with("test-client"));
+
+            // Clients without a certificate are rejected during the handshake
+            let rejected = get_whoami(address, &pki, None).await;
+            assert!(rejected.is_err() || !rejected.unwrap().contains("200"));
+        }
+    }
+
+    mod telemetry {
+        use super::create_test_app;
+        use ai_model_service::config::TelemetryConfig;
+        use ai_model_service::telemetry;
+        use axum::{
+            body::{Body, Bytes},
+            extract::State,
+            http::{Request, StatusCode},
+            routing::post,
+            Router,
+        };
+        use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+        use opentelemetry_proto::tonic::trace::v1::Span;
+        use prost::Message;
+        use serde_json::json;
+        use tokio::sync::mpsc;
+        use tower::util::ServiceExt;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        /// Minimal OTLP/HTTP collector forwarding every received span
+        async fn start_collector() -> (String, mpsc::UnboundedReceiver<Span>) {
+            let (tx, rx) = mpsc::unbounded_channel();
+            let app = Router::new()
+                .route(
+                    "/v1/traces",
+                    post(
+                        |State(tx): State<mpsc::UnboundedSender<Span>>, body: Bytes| async move {
+                            let request = ExportTraceServiceRequest::decode(body).unwrap();
+                            for span in request
+                                .resource_spans
+                                .into_iter()
+                                .flat_map(|r| r.scope_spans)
+                                .flat_map(|s| s.spans)
+                            {
+                                let _ = tx.send(span);
+                            }
+                            StatusCode::OK
+                        },
+                    ),
+                )
+                .with_state(tx);
+
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+            (format!("http://{}/v1/traces", addr), rx)
+        }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+        async fn test_spans_exported_with_remote_parent() {
+            let (endpoint, mut spans) = start_collector().await;
+            let config = TelemetryConfig {
+                enabled: true,
+                otlp_endpoint: endpoint,
+                ..TelemetryConfig::default()
+            };
+            let provider =
+                telemetry::init_tracer_provider(&config, "ai-model-service-test").unwrap();
+            let subscriber = tracing_subscriber::registry().with(telemetry::layer(&provider));
+            let app = create_test_app().await;
+
+            let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+            let request = Request::builder()
+                .method("POST")
+                .uri("/predict")
+                .header("content-type", "application/json")
+                .header(
+                    "traceparent",
+                    format!("00-{}-00f067aa0ba902b7-01", trace_id),
+                )
+                .body(Body::from(json!({ "features": vec![1.0; 10] }).to_string()))
+                .unwrap();
+
+            // The request is awaited on this thread, so the thread-local
+            // subscriber sees every span it creates
+            let response = {
+                let _guard = tracing::subscriber::set_default(subscriber);
+                app.clone().oneshot(request).await.unwrap()
+            };
+            assert_eq!(response.status(), StatusCode::OK);
+            // The HTTP span ends once the response body is done
+            axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+
+            // Exemplars link the latency and confidence buckets to the trace
+            let request = Request::builder()
+                .uri("/metrics")
+                .header("accept", "application/openmetrics-text")
+                .body(Body::empty())
+                .unwrap();
+            let body =
+                axum::body::to_bytes(app.oneshot(request).await.unwrap().into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+            let text = String::from_utf8(body.to_vec()).unwrap();
+            let exemplar = format!("# {{trace_id=\"{}\",request_id=", trace_id);
+            for metric in ["http_request_duration_seconds", "ml_prediction_confidence"] {
+                assert!(
+                    text.lines()
+                        .any(|line| line.starts_with(metric) && line.contains(&exemplar)),
+                    "no {} exemplar with the trace ID",
+                    metric
+                );
+            }
+
+            tokio::task::spawn_blocking(move || provider.shutdown())
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mut received = Vec::new();
+            while let Ok(span) = spans.try_recv() {
+                received.push(span);
+            }
+            let find = |name: &str| {
+                received
+                    .iter()
+                    .find(|span| span.name == name)
+                    .unwrap_or_else(|| panic!("no {} span exported", name))
+            };
+
+            let http = find("POST /predict");
+            let validation = find("validate_features");
+            let inference = find("model_inference");
+
+            // The HTTP span continues the caller's trace
+            assert_eq!(hex(&http.trace_id), trace_id);
+            assert_eq!(hex(&http.parent_span_id), "00f067aa0ba902b7");
+            for span in [validation, inference] {
+                assert_eq!(span.trace_id, http.trace_id);
+            }
+        }
+
+        fn hex(bytes: &[u8]) -> String {
+            bytes.iter().map(|b| format!("{:02x}", b)).collect()
+        }
+    }
 }
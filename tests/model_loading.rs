@@ -0,0 +1,64 @@
+//! Requests that arrive while the model artifact is loading
+//!
+//! Kept out of `integration_tests.rs` so the global model starts out
+//! unloaded in this test process.
+
+use ai_model_service::app::{self, AppState};
+use ai_model_service::config::Config;
+use ai_model_service::models::ml_model;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use serde_json::{json, Value};
+use tower::util::ServiceExt;
+
+#[tokio::test]
+async fn test_requests_during_model_load() {
+    let app = app::create_router(AppState::new(Config::default()).unwrap());
+    let predict = || {
+        Request::builder()
+            .method("POST")
+            .uri("/predict")
+            .header("content-type", "application/json")
+            .body(Body::from(json!({"features": [1.0, 2.0]}).to_string()))
+            .unwrap()
+    };
+    let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    let path = std::env::temp_dir().join(format!("model-{}.json", uuid::Uuid::new_v4()));
+    std::fs::write(
+        &path,
+        r#"{"version": "v-artifact", "weights": [1.0, 1.0], "bias": 0.0}"#,
+    )
+    .unwrap();
+
+    // The artifact loads in the background while requests come in
+    let (loaded_tx, loaded_rx) = tokio::sync::oneshot::channel();
+    let loader = tokio::task::spawn_blocking({
+        let path = path.clone();
+        move || {
+            loaded_rx.blocking_recv().unwrap();
+            ml_model::load_model(Some(&path)).map(|model| model.version().to_string())
+        }
+    });
+
+    let response = app.clone().oneshot(predict()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let response = app.clone().oneshot(get("/models")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // Early requests must not have installed the built-in model
+    loaded_tx.send(()).unwrap();
+    assert_eq!(loader.await.unwrap().unwrap(), "v-artifact");
+    std::fs::remove_file(&path).unwrap();
+
+    let response = app.oneshot(predict()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let prediction: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(prediction["model_version"], "v-artifact");
+    assert_eq!(prediction["prediction"], 3.0);
+}